@@ -1,30 +1,43 @@
 use anyhow::{bail, Context, Error, Result};
-use crossterm::{cursor, terminal, QueueableCommand};
+use crossterm::{cursor, QueueableCommand};
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     env,
-    fs::{File, OpenOptions},
-    io::{Read, Seek, StdoutLock, Write},
-    path::{Path, MAIN_SEPARATOR_STR},
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, Read, Seek, StdoutLock, Write},
+    mem,
+    num::NonZeroUsize,
+    ops::Range,
+    path::{Path, PathBuf, MAIN_SEPARATOR_STR},
     process::{Command, Stdio},
+    str,
     sync::{
         atomic::{AtomicUsize, Ordering::Relaxed},
         mpsc,
     },
     thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
     clear_terminal,
-    cmd::CmdRunner,
+    cmd::{CmdRunner, ExerciseRunner},
     embedded::EMBEDDED_FILES,
-    exercise::{Exercise, RunnableExercise},
-    info_file::ExerciseInfo,
+    exercise::{Exercise, RunnableExercise, OUTPUT_CAPACITY},
+    info_file::{Difficulty, ExerciseInfo},
+    messages::quoted_list,
     term::{self, CheckProgressVisualizer},
+    theme::Theme,
 };
 
 const STATE_FILE_NAME: &str = ".rustlings-state.txt";
+// Lists the names of exercises to exclude without removing them from `info.toml`.
+const IGNORE_FILE_NAME: &str = ".rustlings-ignore";
 const DEFAULT_CHECK_PARALLELISM: usize = 8;
+// The directory of the quizzes that test the topics of the exercises before them.
+const QUIZZES_DIR: &str = "quizzes";
+/// Reports of problems with exercises are appended to this file.
+pub const REPORTS_FILE_NAME: &str = "reports.txt";
 
 #[must_use]
 pub enum ExercisesProgress {
@@ -41,6 +54,43 @@ pub enum StateFileStatus {
     NotRead,
 }
 
+/// The progress of the exercises in one directory.
+pub struct GroupProgress {
+    pub name: &'static str,
+    pub n_done: u16,
+    pub n_exercises: u16,
+}
+
+/// Completion statistics shown in the watch mode.
+pub struct Stats {
+    pub n_exercises: u16,
+    pub n_done: u16,
+    /// The number of consecutive done exercises up to the current one.
+    pub streak: u16,
+    /// The time spent on all exercises in the watch mode.
+    pub time_spent: Duration,
+    pub n_hints_viewed: u16,
+    /// How often hints were shown in total.
+    pub n_hints_shown: u32,
+    pub n_solutions_peeked: u16,
+    /// The number of exercises done for the first time on each of the last `TREND_DAYS` days
+    /// ending today. `None` if there are less than two days of history.
+    pub daily_done: Option<Vec<u16>>,
+}
+
+/// The number of days in the progress trend of the stats.
+pub const TREND_DAYS: usize = 14;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The result of checking an exercise while checking all exercises.
+pub struct ExerciseCheckResult {
+    pub exercise_ind: usize,
+    pub name: &'static str,
+    pub success: bool,
+    /// The output is only captured if requested.
+    pub output: Option<Vec<u8>>,
+}
+
 #[derive(Clone, Copy)]
 pub enum CheckProgress {
     None,
@@ -49,126 +99,546 @@ pub enum CheckProgress {
     Pending,
 }
 
+/// Only the exercises matching all set fields are loaded.
+#[derive(Default)]
+pub struct ExerciseFilter {
+    /// A substring or glob pattern of the name. See `matches_filter`.
+    pub name: Option<String>,
+    /// Exercises without a difficulty always match.
+    pub difficulty: Option<Difficulty>,
+}
+
+impl ExerciseFilter {
+    fn matches(&self, exercise: &Exercise) -> bool {
+        self.name
+            .as_deref()
+            .map_or(true, |name| matches_filter(exercise.name, name))
+            && self.difficulty.map_or(true, |difficulty| {
+                exercise.difficulty.map_or(true, |exercise_difficulty| {
+                    exercise_difficulty == difficulty
+                })
+            })
+    }
+}
+
 pub struct AppState {
     current_exercise_ind: usize,
     exercises: Vec<Exercise>,
     // Caches the number of done exercises to avoid iterating over all exercises every time.
     n_done: u16,
+    // Indices of skipped exercises in the order of skipping.
+    // They are only chosen as the next pending exercise after all other pending exercises.
+    skipped: Vec<usize>,
     final_message: String,
-    state_file: File,
+    // `None` if the state isn't persisted (in tests).
+    state_file: Option<File>,
     // Preallocated buffer for reading and writing the state file.
     file_buf: Vec<u8>,
     official_exercises: bool,
-    cmd_runner: CmdRunner,
+    // Exercises not matching the filter or ignored.
+    // Only kept to preserve their state in the state file.
+    excluded_exercises: Vec<Exercise>,
+    filter: ExerciseFilter,
+    cmd_runner: Box<dyn ExerciseRunner>,
     // Running in VS Code.
     vs_code: bool,
+    theme: Theme,
+    // Never show or write solutions (e.g. in classrooms).
+    hide_solutions: bool,
+    // Shuffle the exercise groups with this seed.
+    seed: Option<u64>,
+    // Exercise names to the content of their files before the last reset to allow undoing it.
+    reset_backups: HashMap<&'static str, String>,
+    // Exercises can't become current before their prerequisites are done.
+    enforce_prerequisites: bool,
 }
 
-impl AppState {
-    pub fn new(
-        exercise_infos: Vec<ExerciseInfo>,
-        final_message: String,
-    ) -> Result<(Self, StateFileStatus)> {
-        let cmd_runner = CmdRunner::build()?;
-        let mut state_file = OpenOptions::new()
-            .create(true)
-            .read(true)
-            .write(true)
-            .truncate(false)
-            .open(STATE_FILE_NAME)
-            .with_context(|| {
-                format!("Failed to open or create the state file {STATE_FILE_NAME}")
-            })?;
+// Convert the exercises' info into exercises without any state.
+// The hint files are read here.
+// Also returns warnings about the infos which are shown to the user.
+fn exercises_from_infos(exercise_infos: Vec<ExerciseInfo>) -> Result<(Vec<Exercise>, Vec<String>)> {
+    // Only allowing earlier exercises rules out cycles which would lock exercises forever.
+    for (info_ind, exercise_info) in exercise_infos.iter().enumerate() {
+        for prerequisite in &exercise_info.prerequisites {
+            if !exercise_infos[..info_ind]
+                .iter()
+                .any(|earlier| earlier.name == *prerequisite)
+            {
+                bail!(
+                    "The prerequisite `{prerequisite}` of the exercise `{}` isn't an exercise before it in `info.toml`",
+                    exercise_info.name,
+                );
+            }
+        }
+    }
 
-        let dir_canonical_path = term::canonicalize("exercises");
-        let mut exercises = exercise_infos
-            .into_iter()
-            .map(|exercise_info| {
-                // Leaking to be able to borrow in the watch mode `Table`.
-                // Leaking is not a problem because the `AppState` instance lives until
-                // the end of the program.
-                let path = exercise_info.path().leak();
-                let name = exercise_info.name.leak();
-                let dir = exercise_info.dir.map(|dir| &*dir.leak());
-                let hint = exercise_info.hint.leak().trim_ascii();
-
-                let canonical_path = dir_canonical_path.as_deref().map(|dir_canonical_path| {
-                    let mut canonical_path;
-                    if let Some(dir) = dir {
-                        canonical_path = String::with_capacity(
-                            2 + dir_canonical_path.len() + dir.len() + name.len(),
-                        );
-                        canonical_path.push_str(dir_canonical_path);
-                        canonical_path.push_str(MAIN_SEPARATOR_STR);
-                        canonical_path.push_str(dir);
-                    } else {
-                        canonical_path =
-                            String::with_capacity(1 + dir_canonical_path.len() + name.len());
-                        canonical_path.push_str(dir_canonical_path);
+    let dir_canonical_path = term::canonicalize("exercises");
+    let mut warnings = Vec::new();
+    let exercises = exercise_infos
+        .into_iter()
+        .enumerate()
+        .map(|(info_ind, exercise_info)| {
+            // Leaking to be able to borrow in the watch mode `Table`.
+            // Leaking is not a problem because the `AppState` instance lives until
+            // the end of the program.
+            let path = exercise_info.path().leak();
+            let name = exercise_info.name.leak();
+            let dir = exercise_info.dir.map(|dir| &*dir.leak());
+            let stdin = exercise_info.stdin.map(|stdin| &*stdin.leak());
+            let pre_check = exercise_info.pre_check.map(|cmd| &*cmd.leak());
+            let post_check = exercise_info.post_check.map(|cmd| &*cmd.leak());
+            let expected_output = exercise_info
+                .expected_output
+                .map(|expected_output| &*expected_output.leak());
+            let hints = match &exercise_info.hint_file {
+                Some(hint_file) => {
+                    if exercise_info.hint.iter().next().is_some() {
+                        warnings.push(format!(
+                            "The exercise `{name}` has a hint and a hint file. Only the hint file `{hint_file}` is used",
+                        ));
                     }
 
+                    let hint = fs::read_to_string(hint_file).with_context(|| {
+                        format!("Failed to read the hint file `{hint_file}` of the exercise `{name}`")
+                    })?;
+                    vec![hint]
+                }
+                None => exercise_info.hint.into_vec(),
+            };
+            let hints = hints
+                .into_iter()
+                .map(|hint| hint.leak().trim_ascii())
+                .collect::<Vec<_>>()
+                .leak();
+            let prerequisites = exercise_info
+                .prerequisites
+                .into_iter()
+                .map(|prerequisite| &*prerequisite.leak())
+                .collect::<Vec<_>>()
+                .leak();
+
+            let canonical_path = dir_canonical_path.as_deref().map(|dir_canonical_path| {
+                let mut canonical_path;
+                if let Some(dir) = dir {
+                    canonical_path = String::with_capacity(
+                        2 + dir_canonical_path.len() + dir.len() + name.len(),
+                    );
+                    canonical_path.push_str(dir_canonical_path);
                     canonical_path.push_str(MAIN_SEPARATOR_STR);
-                    canonical_path.push_str(name);
-                    canonical_path.push_str(".rs");
-                    canonical_path
-                });
-
-                Exercise {
-                    dir,
-                    name,
-                    path,
-                    canonical_path,
-                    test: exercise_info.test,
-                    strict_clippy: exercise_info.strict_clippy,
-                    hint,
-                    // Updated below.
-                    done: false,
+                    canonical_path.push_str(dir);
+                } else {
+                    canonical_path =
+                        String::with_capacity(1 + dir_canonical_path.len() + name.len());
+                    canonical_path.push_str(dir_canonical_path);
                 }
+
+                canonical_path.push_str(MAIN_SEPARATOR_STR);
+                canonical_path.push_str(name);
+                canonical_path.push_str(".rs");
+                canonical_path
+            });
+
+            Ok(Exercise {
+                info_ind,
+                dir,
+                name,
+                path,
+                canonical_path,
+                test: exercise_info.test,
+                strict_clippy: exercise_info.strict_clippy,
+                deny_warnings: exercise_info.deny_warnings,
+                stdin,
+                pre_check,
+                post_check,
+                expected_output,
+                difficulty: exercise_info.difficulty,
+                optional: exercise_info.optional,
+                prerequisites,
+                hints,
+                // Updated by `read_state`.
+                done: false,
+                hint_viewed: false,
+                n_hints_shown: 0,
+                solution_peeked: false,
+                time_spent: Duration::ZERO,
+                bookmarked: false,
+                notes: Vec::new(),
+                done_mtime: None,
+                first_done_at: None,
             })
-            .collect::<Vec<_>>();
+        })
+        .collect::<Result<_>>()?;
 
-        let mut current_exercise_ind = 0;
-        let mut n_done = 0;
-        let mut file_buf = Vec::with_capacity(2048);
-        let state_file_status = 'block: {
-            if state_file.read_to_end(&mut file_buf).is_err() {
-                break 'block StateFileStatus::NotRead;
+    Ok((exercises, warnings))
+}
+
+// SplitMix64: A small pseudorandom number generator which is enough for shuffling.
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// Shuffle the order of the exercise groups (directories) reproducibly using the seed.
+// The order inside a group is kept because its exercises build on each other.
+// Quizzes stay after the group before them since they test its topic.
+fn shuffle_groups(exercises: &mut Vec<Exercise>, seed: u64) {
+    let mut groups: Vec<Vec<Exercise>> = Vec::new();
+    for exercise in exercises.drain(..) {
+        match groups.last_mut() {
+            Some(group)
+                if exercise.dir == Some(QUIZZES_DIR)
+                    || (exercise.dir.is_some() && group[0].dir == exercise.dir) =>
+            {
+                group.push(exercise);
             }
+            _ => groups.push(vec![exercise]),
+        }
+    }
 
-            // See `Self::write` for more information about the file format.
-            let mut lines = file_buf.split(|c| *c == b'\n').skip(2);
+    // Fisher-Yates
+    let mut state = seed;
+    for ind in (1..groups.len()).rev() {
+        let other_ind = (next_random(&mut state) % (ind as u64 + 1)) as usize;
+        groups.swap(ind, other_ind);
+    }
 
-            let Some(current_exercise_name) = lines.next() else {
-                break 'block StateFileStatus::NotRead;
-            };
+    // Take the first group whose prerequisites are already placed to never present an exercise
+    // before its prerequisites. There is always such a group because the original order is valid.
+    let mut placed = HashSet::with_capacity(exercises.capacity());
+    while !groups.is_empty() {
+        let group_ind = groups
+            .iter()
+            .position(|group| {
+                group.iter().all(|exercise| {
+                    exercise.prerequisites.iter().all(|prerequisite| {
+                        placed.contains(prerequisite)
+                            || group.iter().any(|other| other.name == *prerequisite)
+                    })
+                })
+            })
+            .unwrap_or(0);
 
-            if current_exercise_name.is_empty() || lines.next().is_none() {
-                break 'block StateFileStatus::NotRead;
-            }
+        let group = groups.remove(group_ind);
+        placed.extend(group.iter().map(|exercise| exercise.name));
+        exercises.extend(group);
+    }
+}
+
+// The modification time of a file in seconds since the Unix epoch.
+fn file_mtime(path: &str) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+fn unix_now() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+// Update the modification time of a done exercise that passed again after its file changed.
+// Otherwise, it would be marked as pending on the next start. Returns `true` if it changed.
+fn refresh_done_mtime(exercise: &mut Exercise) -> bool {
+    let mtime = file_mtime(exercise.path);
+    if exercise.done_mtime == mtime {
+        return false;
+    }
+
+    exercise.done_mtime = mtime;
+    true
+}
+
+// Only the first time counts towards the progress trend.
+fn record_first_done(exercise: &mut Exercise) {
+    if exercise.first_done_at.is_none() {
+        exercise.first_done_at = unix_now();
+    }
+}
+
+// The number of exercises done for the first time on each of the `TREND_DAYS` days ending with
+// the day `today` (days since the Unix epoch in UTC).
+// `None` if the first exercise was done today or no exercise was done yet.
+fn daily_done(exercises: &[Exercise], today: u64) -> Option<Vec<u16>> {
+    let days = exercises
+        .iter()
+        .filter_map(|exercise| exercise.first_done_at)
+        .map(|secs| secs / SECS_PER_DAY);
+    if days.clone().min()? >= today {
+        return None;
+    }
+
+    let mut daily_done = vec![0; TREND_DAYS];
+    for day in days {
+        // Future days are possible if the clock was changed.
+        if let Some(n_days_ago) = today
+            .checked_sub(day)
+            .filter(|&n| (n as usize) < TREND_DAYS)
+        {
+            daily_done[TREND_DAYS - 1 - n_days_ago as usize] += 1;
+        }
+    }
+
+    Some(daily_done)
+}
+
+// Apply the content of the state file to the exercises.
+// Return the index of the current exercise if it was found and the number of done exercises.
+// Return `None` without changing the exercises if the content is invalid.
+fn read_state(file_buf: &[u8], exercises: &mut [Exercise]) -> Option<(Option<usize>, u16)> {
+    // See `AppState::write` for more information about the file format.
+    let mut lines = file_buf.split(|c| *c == b'\n').skip(2);
+
+    let current_exercise_name = lines.next()?;
+
+    if current_exercise_name.is_empty() || lines.next().is_none() {
+        return None;
+    }
+
+    let mut current_exercise_ind = None;
+    let mut n_done = 0;
+    let mut done_exercises = HashSet::with_capacity(exercises.len());
+
+    for done_exercise_name in lines.by_ref() {
+        if done_exercise_name.is_empty() {
+            break;
+        }
+        done_exercises.insert(done_exercise_name);
+    }
 
-            let mut done_exercises = HashSet::with_capacity(exercises.len());
+    for metadata_line in lines {
+        let mut parts = metadata_line.splitn(3, |c| *c == b' ');
+        let (Some(key), Some(exercise_name)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let value = parts.next().unwrap_or_default();
+
+        let Some(exercise) = exercises
+            .iter_mut()
+            .find(|exercise| exercise.name.as_bytes() == exercise_name)
+        else {
+            continue;
+        };
 
-            for done_exercise_name in lines {
-                if done_exercise_name.is_empty() {
-                    break;
+        match key {
+            b"hint_viewed" => exercise.hint_viewed = true,
+            b"hints_shown" => {
+                if let Some(n) = str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                {
+                    exercise.n_hints_shown = n;
                 }
-                done_exercises.insert(done_exercise_name);
             }
-
-            for (ind, exercise) in exercises.iter_mut().enumerate() {
-                if done_exercises.contains(exercise.name.as_bytes()) {
-                    exercise.done = true;
-                    n_done += 1;
+            b"solution_peeked" => exercise.solution_peeked = true,
+            b"bookmarked" => exercise.bookmarked = true,
+            b"note" => {
+                if let Ok(note) = str::from_utf8(value) {
+                    exercise.notes.push(note.to_string());
                 }
-
-                if exercise.name.as_bytes() == current_exercise_name {
-                    current_exercise_ind = ind;
+            }
+            b"time_spent" => {
+                if let Some(secs) = str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok())
+                {
+                    exercise.time_spent = Duration::from_secs(secs);
                 }
             }
+            b"done_mtime" => {
+                exercise.done_mtime = str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok());
+            }
+            b"first_done_at" => {
+                exercise.first_done_at = str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse().ok());
+            }
+            // Ignore unknown keys written by newer versions.
+            _ => (),
+        }
+    }
+
+    for (ind, exercise) in exercises.iter_mut().enumerate() {
+        if done_exercises.contains(exercise.name.as_bytes()) {
+            exercise.done = true;
+            n_done += 1;
+        }
+
+        if exercise.name.as_bytes() == current_exercise_name {
+            current_exercise_ind = Some(ind);
+        }
+    }
+
+    Some((current_exercise_ind, n_done))
+}
+
+// Check if the exercise name matches the filter.
+// The filter is a glob pattern if it contains `*`. Otherwise, it is a substring.
+fn matches_filter(name: &str, filter: &str) -> bool {
+    if !filter.contains('*') {
+        return name.contains(filter);
+    }
+
+    // The first and last parts are anchored at the start and end of the name.
+    let mut parts = filter.split('*');
+    let first_part = parts.next().unwrap_or_default();
+    let last_part = parts.next_back().unwrap_or_default();
+
+    let Some(mut rest) = name.strip_prefix(first_part) else {
+        return false;
+    };
+
+    for part in parts {
+        let Some(ind) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[ind + part.len()..];
+    }
+
+    rest.ends_with(last_part)
+}
+
+// Replace control characters like newlines with spaces and trim the text.
+fn single_line(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// The absolute path of the state file in the current directory.
+pub fn state_file_path() -> Result<PathBuf> {
+    let current_dir = env::current_dir().context("Failed to get the current directory")?;
+    Ok(current_dir.join(STATE_FILE_NAME))
+}
+
+// One exercise name per line. Empty lines and lines starting with `#` are skipped.
+fn parse_ignored_names(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+// The ignore file is optional.
+fn read_ignored_names() -> Result<HashSet<String>> {
+    match fs::read_to_string(IGNORE_FILE_NAME) {
+        Ok(content) => Ok(parse_ignored_names(&content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(e) => {
+            Err(Error::from(e).context(format!("Failed to read the file {IGNORE_FILE_NAME}")))
+        }
+    }
+}
 
-            StateFileStatus::Read
+// Move the exercises not matching the filter or listed in the ignore file out of `exercises`
+// and return them. The current exercise is kept if it isn't excluded.
+// Otherwise, the first pending exercise becomes the current one.
+fn apply_filter(
+    filter: &ExerciseFilter,
+    exercises: &mut Vec<Exercise>,
+    current_exercise_ind: &mut usize,
+    n_done: &mut u16,
+) -> Result<Vec<Exercise>> {
+    let ignored_names = read_ignored_names()?;
+    if filter.name.is_none() && filter.difficulty.is_none() && ignored_names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let current_exercise_name = exercises[*current_exercise_ind].name;
+    let (matching, excluded): (Vec<_>, Vec<_>) = mem::take(exercises)
+        .into_iter()
+        .partition(|exercise| !ignored_names.contains(exercise.name) && filter.matches(exercise));
+
+    if matching.is_empty() {
+        if let Some(name) = &filter.name {
+            bail!("No exercise matches the filter `{name}` and isn't listed in {IGNORE_FILE_NAME}");
+        }
+        if let Some(difficulty) = filter.difficulty {
+            bail!(
+                "No exercise has the difficulty `{}` and isn't listed in {IGNORE_FILE_NAME}",
+                difficulty.as_str(),
+            );
+        }
+        bail!("All exercises are listed in {IGNORE_FILE_NAME}");
+    }
+
+    *current_exercise_ind = matching
+        .iter()
+        .position(|exercise| exercise.name == current_exercise_name)
+        .or_else(|| matching.iter().position(|exercise| !exercise.done))
+        .unwrap_or(0);
+    *n_done = matching.iter().filter(|exercise| exercise.done).count() as u16;
+    *exercises = matching;
+
+    Ok(excluded)
+}
+
+impl AppState {
+    /// Only the exercises matching `filter` are loaded.
+    /// Exercises listed in the ignore file are never loaded.
+    /// Also returns the warnings about the exercise infos.
+    pub fn new(
+        exercise_infos: Vec<ExerciseInfo>,
+        final_message: String,
+        cmd_runner: CmdRunner,
+        theme: Theme,
+        filter: ExerciseFilter,
+        hide_solutions: bool,
+        seed: Option<u64>,
+    ) -> Result<(Self, StateFileStatus, Vec<String>)> {
+        let mut state_file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(false)
+            .open(STATE_FILE_NAME)
+            .with_context(|| {
+                format!("Failed to open or create the state file {STATE_FILE_NAME}")
+            })?;
+
+        let (mut exercises, warnings) = exercises_from_infos(exercise_infos)?;
+        if let Some(seed) = seed {
+            shuffle_groups(&mut exercises, seed);
+        }
+
+        let mut file_buf = Vec::with_capacity(2048);
+        let state = state_file
+            .read_to_end(&mut file_buf)
+            .ok()
+            .and_then(|_| read_state(&file_buf, &mut exercises));
+        let (state_file_status, mut current_exercise_ind, mut n_done) = match state {
+            // Resume at the exercise that was current when quitting, even if it is done.
+            // Fall back to the first pending exercise if it was removed.
+            Some((current_exercise_ind, n_done)) => (
+                StateFileStatus::Read,
+                current_exercise_ind
+                    .or_else(|| exercises.iter().position(|exercise| !exercise.done))
+                    .unwrap_or(0),
+                n_done,
+            ),
+            None => (StateFileStatus::NotRead, 0, 0),
         };
 
+        let excluded_exercises = apply_filter(
+            &filter,
+            &mut exercises,
+            &mut current_exercise_ind,
+            &mut n_done,
+        )?;
+
         file_buf.clear();
         file_buf.extend_from_slice(STATE_FILE_HEADER);
 
@@ -176,15 +646,60 @@ impl AppState {
             current_exercise_ind,
             exercises,
             n_done,
+            skipped: Vec::new(),
             final_message,
-            state_file,
+            state_file: Some(state_file),
             file_buf,
             official_exercises: !Path::new("info.toml").exists(),
-            cmd_runner,
+            excluded_exercises,
+            filter,
+            cmd_runner: Box::new(cmd_runner),
             vs_code: env::var_os("TERM_PROGRAM").is_some_and(|v| v == "vscode"),
+            theme,
+            hide_solutions,
+            seed,
+            reset_backups: HashMap::new(),
+            enforce_prerequisites: false,
         };
 
-        Ok((slf, state_file_status))
+        Ok((slf, state_file_status, warnings))
+    }
+
+    /// Build an app state from exercise infos in memory without reading or writing a state file.
+    /// All exercises start pending. Meant for tests.
+    #[cfg(test)]
+    pub fn in_memory(
+        exercise_infos: Vec<ExerciseInfo>,
+        cmd_runner: impl ExerciseRunner + 'static,
+    ) -> Self {
+        let (exercises, _) =
+            exercises_from_infos(exercise_infos).expect("Failed to read a hint file");
+        Self::from_exercises(exercises, cmd_runner)
+    }
+
+    #[cfg(test)]
+    fn from_exercises(exercises: Vec<Exercise>, cmd_runner: impl ExerciseRunner + 'static) -> Self {
+        let n_done = exercises.iter().filter(|exercise| exercise.done).count() as u16;
+
+        Self {
+            current_exercise_ind: 0,
+            exercises,
+            n_done,
+            skipped: Vec::new(),
+            final_message: String::new(),
+            state_file: None,
+            file_buf: STATE_FILE_HEADER.to_vec(),
+            official_exercises: true,
+            excluded_exercises: Vec::new(),
+            filter: ExerciseFilter::default(),
+            cmd_runner: Box::new(cmd_runner),
+            vs_code: false,
+            theme: Theme::default(),
+            hide_solutions: false,
+            seed: None,
+            reset_backups: HashMap::new(),
+            enforce_prerequisites: false,
+        }
     }
 
     #[inline]
@@ -213,8 +728,8 @@ impl AppState {
     }
 
     #[inline]
-    pub fn cmd_runner(&self) -> &CmdRunner {
-        &self.cmd_runner
+    pub fn cmd_runner(&self) -> &dyn ExerciseRunner {
+        &*self.cmd_runner
     }
 
     #[inline]
@@ -222,6 +737,122 @@ impl AppState {
         self.vs_code
     }
 
+    #[inline]
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    // The number of done and all exercises which are (not) optional.
+    fn progress_of(&self, optional: bool) -> (u16, u16) {
+        self.exercises
+            .iter()
+            .filter(|exercise| exercise.optional == optional)
+            .fold((0, 0), |(n_done, n_exercises), exercise| {
+                (n_done + u16::from(exercise.done), n_exercises + 1)
+            })
+    }
+
+    /// The number of done and all exercises which are required to finish.
+    #[inline]
+    pub fn core_progress(&self) -> (u16, u16) {
+        self.progress_of(false)
+    }
+
+    /// The number of done and all optional challenges.
+    #[inline]
+    pub fn challenge_progress(&self) -> (u16, u16) {
+        self.progress_of(true)
+    }
+
+    /// Whether any exercise has a difficulty. Otherwise, it isn't shown.
+    pub fn has_difficulties(&self) -> bool {
+        self.exercises
+            .iter()
+            .any(|exercise| exercise.difficulty.is_some())
+    }
+
+    #[inline]
+    pub fn official_exercises(&self) -> bool {
+        self.official_exercises
+    }
+
+    /// The progress of the exercise groups in the order of their first exercise.
+    /// Exercises are grouped by their directory without a numeric prefix like `01_`.
+    pub fn group_progresses(&self) -> Vec<GroupProgress> {
+        let mut groups: Vec<GroupProgress> = Vec::new();
+
+        for exercise in &self.exercises {
+            let name = exercise.dir.map_or("exercises", |dir| {
+                let name = dir.trim_start_matches(|c: char| c.is_ascii_digit());
+                match name.strip_prefix('_') {
+                    Some(name) if !name.is_empty() => name,
+                    _ => dir,
+                }
+            });
+
+            // There are only a few groups.
+            let group = match groups.iter_mut().find(|group| group.name == name) {
+                Some(group) => group,
+                None => {
+                    groups.push(GroupProgress {
+                        name,
+                        n_done: 0,
+                        n_exercises: 0,
+                    });
+                    groups.last_mut().unwrap()
+                }
+            };
+
+            group.n_exercises += 1;
+            if exercise.done {
+                group.n_done += 1;
+            }
+        }
+
+        groups
+    }
+
+    pub fn stats(&self) -> Stats {
+        // The current exercise doesn't break the streak if it isn't done yet.
+        let streak_end = if self.current_exercise().done {
+            self.current_exercise_ind + 1
+        } else {
+            self.current_exercise_ind
+        };
+        let streak = self.exercises[..streak_end]
+            .iter()
+            .rev()
+            .take_while(|exercise| exercise.done)
+            .count() as u16;
+
+        Stats {
+            n_exercises: self.exercises.len() as u16,
+            n_done: self.n_done,
+            streak,
+            time_spent: self
+                .exercises
+                .iter()
+                .map(|exercise| exercise.time_spent)
+                .sum(),
+            n_hints_viewed: self
+                .exercises
+                .iter()
+                .filter(|exercise| exercise.hint_viewed)
+                .count() as u16,
+            n_hints_shown: self
+                .exercises
+                .iter()
+                .map(|exercise| exercise.n_hints_shown)
+                .sum(),
+            n_solutions_peeked: self
+                .exercises
+                .iter()
+                .filter(|exercise| exercise.solution_peeked)
+                .count() as u16,
+            daily_done: unix_now().and_then(|now| daily_done(&self.exercises, now / SECS_PER_DAY)),
+        }
+    }
+
     // Write the state file.
     // The file's format is very simple:
     // - The first line is a comment.
@@ -229,7 +860,10 @@ impl AppState {
     // - The third line is the name of the current exercise. It must end with `\n` even if there
     // are no done exercises.
     // - The fourth line is an empty line.
-    // - All remaining lines are the names of done exercises.
+    // - The following lines are the names of done exercises.
+    // - An empty line.
+    // - All remaining lines are exercise metadata in the form `KEY EXERCISE_NAME[ VALUE]`.
+    //   Older versions stop reading at the empty line before them.
     fn write(&mut self) -> Result<()> {
         self.file_buf.truncate(STATE_FILE_HEADER.len());
 
@@ -237,54 +871,307 @@ impl AppState {
             .extend_from_slice(self.current_exercise().name.as_bytes());
         self.file_buf.push(b'\n');
 
-        for exercise in &self.exercises {
+        // The state of the excluded exercises must not get lost.
+        for exercise in self.exercises.iter().chain(&self.excluded_exercises) {
             if exercise.done {
                 self.file_buf.push(b'\n');
                 self.file_buf.extend_from_slice(exercise.name.as_bytes());
             }
         }
 
-        self.state_file
+        self.file_buf.extend_from_slice(b"\n\n");
+        for exercise in self.exercises.iter().chain(&self.excluded_exercises) {
+            if exercise.hint_viewed {
+                push_metadata_line(&mut self.file_buf, b"hint_viewed", exercise.name, b"");
+            }
+
+            if exercise.n_hints_shown > 0 {
+                push_metadata_line(
+                    &mut self.file_buf,
+                    b"hints_shown",
+                    exercise.name,
+                    exercise.n_hints_shown.to_string().as_bytes(),
+                );
+            }
+
+            if exercise.solution_peeked {
+                push_metadata_line(&mut self.file_buf, b"solution_peeked", exercise.name, b"");
+            }
+
+            if exercise.bookmarked {
+                push_metadata_line(&mut self.file_buf, b"bookmarked", exercise.name, b"");
+            }
+
+            for note in &exercise.notes {
+                push_metadata_line(&mut self.file_buf, b"note", exercise.name, note.as_bytes());
+            }
+
+            let secs = exercise.time_spent.as_secs();
+            if secs > 0 {
+                push_metadata_line(
+                    &mut self.file_buf,
+                    b"time_spent",
+                    exercise.name,
+                    secs.to_string().as_bytes(),
+                );
+            }
+
+            if let Some(mtime) = exercise.done_mtime.filter(|_| exercise.done) {
+                push_metadata_line(
+                    &mut self.file_buf,
+                    b"done_mtime",
+                    exercise.name,
+                    mtime.to_string().as_bytes(),
+                );
+            }
+
+            if let Some(first_done_at) = exercise.first_done_at {
+                push_metadata_line(
+                    &mut self.file_buf,
+                    b"first_done_at",
+                    exercise.name,
+                    first_done_at.to_string().as_bytes(),
+                );
+            }
+        }
+
+        let Some(state_file) = &mut self.state_file else {
+            return Ok(());
+        };
+        state_file
             .rewind()
             .with_context(|| format!("Failed to rewind the state file {STATE_FILE_NAME}"))?;
-        self.state_file
+        state_file
             .set_len(0)
             .with_context(|| format!("Failed to truncate the state file {STATE_FILE_NAME}"))?;
-        self.state_file
+        state_file
             .write_all(&self.file_buf)
             .with_context(|| format!("Failed to write the state file {STATE_FILE_NAME}"))?;
 
         Ok(())
     }
 
-    pub fn set_current_exercise_ind(&mut self, exercise_ind: usize) -> Result<()> {
-        if exercise_ind == self.current_exercise_ind {
-            return Ok(());
-        }
+    /// Replace the exercises after their info was changed.
+    /// The state of the exercises that still exist is kept.
+    /// If the current exercise was removed, the first pending exercise becomes the current one.
+    /// Returns the warnings about the new exercise infos.
+    pub fn reload(
+        &mut self,
+        exercise_infos: Vec<ExerciseInfo>,
+        final_message: String,
+    ) -> Result<Vec<String>> {
+        let skipped_names = self
+            .skipped
+            .iter()
+            .map(|ind| self.exercises[*ind].name)
+            .collect::<Vec<_>>();
+
+        // Write the current state to the buffer to read it again for the new exercises.
+        self.write()?;
+        let (mut exercises, warnings) = exercises_from_infos(exercise_infos)?;
+        if let Some(seed) = self.seed {
+            shuffle_groups(&mut exercises, seed);
+        }
+        let (current_exercise_ind, mut n_done) = read_state(&self.file_buf, &mut exercises)
+            .context("Failed to read the state of the exercises after reloading")?;
+
+        let mut current_exercise_ind = current_exercise_ind
+            .or_else(|| exercises.iter().position(|exercise| !exercise.done))
+            .unwrap_or(0);
+        // Also picks up changes of the ignore file.
+        self.excluded_exercises = apply_filter(
+            &self.filter,
+            &mut exercises,
+            &mut current_exercise_ind,
+            &mut n_done,
+        )?;
+
+        self.current_exercise_ind = current_exercise_ind;
+        self.skipped = skipped_names
+            .into_iter()
+            .filter_map(|name| exercises.iter().position(|exercise| exercise.name == name))
+            .collect();
+        self.exercises = exercises;
+        self.n_done = n_done;
+        self.final_message = final_message;
+
+        self.write()?;
+
+        Ok(warnings)
+    }
+
+    // Remember that the hint of the current exercise was shown and save.
+    // A reset of the exercise doesn't change this.
+    pub fn set_current_hint_viewed(&mut self) -> Result<()> {
+        let exercise = &mut self.exercises[self.current_exercise_ind];
+        exercise.hint_viewed = true;
+        // Counted for every shown hint to discourage showing them all at once.
+        exercise.n_hints_shown = exercise.n_hints_shown.saturating_add(1);
+        self.write()
+    }
+
+    // Remember that the first lines of the solution of the current exercise were shown and save.
+    pub fn set_current_solution_peeked(&mut self) -> Result<()> {
+        self.exercises[self.current_exercise_ind].solution_peeked = true;
+        self.write()
+    }
+
+    // Toggle the bookmark of the current exercise and save.
+    // Neither resetting nor finishing the exercise changes the bookmark.
+    pub fn toggle_current_bookmark(&mut self) -> Result<()> {
+        let exercise = &mut self.exercises[self.current_exercise_ind];
+        exercise.bookmarked = !exercise.bookmarked;
+
+        self.write()
+    }
+
+    // Add a note to the current exercise and save.
+    // Neither resetting nor finishing the exercise removes its notes.
+    pub fn add_current_note(&mut self, note: &str) -> Result<()> {
+        // A note is stored in a single line of the state file.
+        let note = single_line(note);
+        if note.is_empty() {
+            return Ok(());
+        }
+
+        self.exercises[self.current_exercise_ind].notes.push(note);
+        self.write()
+    }
+
+    // Append a line with the name of the current exercise and the message to the reports file.
+    pub fn report_current_exercise(&self, message: &str) -> Result<()> {
+        // One report per line.
+        let message = single_line(message);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(REPORTS_FILE_NAME)
+            .with_context(|| format!("Failed to open the file {REPORTS_FILE_NAME}"))?;
+        writeln!(file, "{}: {message}", self.current_exercise().name)
+            .with_context(|| format!("Failed to write to the file {REPORTS_FILE_NAME}"))
+    }
+
+    pub fn clear_current_notes(&mut self) -> Result<()> {
+        self.exercises[self.current_exercise_ind].notes.clear();
+        self.write()
+    }
+
+    /// Add to the time spent on an exercise and save.
+    pub fn add_time_spent(&mut self, exercise_ind: usize, duration: Duration) -> Result<()> {
+        let exercise = self
+            .exercises
+            .get_mut(exercise_ind)
+            .context(BAD_INDEX_ERR)?;
+
+        let old_secs = exercise.time_spent.as_secs();
+        exercise.time_spent += duration;
+
+        // Avoid writing the state file for changes that aren't saved anyway.
+        if exercise.time_spent.as_secs() != old_secs {
+            self.write()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_current_exercise_ind(&mut self, exercise_ind: usize) -> Result<()> {
+        if exercise_ind == self.current_exercise_ind {
+            return Ok(());
+        }
+
+        if exercise_ind >= self.exercises.len() {
+            bail!(BAD_INDEX_ERR);
+        }
+        if let Some(locked_message) = self.locked_message(exercise_ind) {
+            bail!("{locked_message}");
+        }
+
+        self.current_exercise_ind = exercise_ind;
+
+        self.write()
+    }
+
+    /// Lock exercises until their prerequisites are done.
+    /// Moves on to the first pending exercise if the current one is locked.
+    pub fn enforce_prerequisites(&mut self) -> Result<()> {
+        self.enforce_prerequisites = true;
+
+        if self.is_locked(self.current_exercise_ind) {
+            self.set_current_exercise_to_first_pending()?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the prerequisites are enforced and not all of them are done.
+    /// Prerequisites excluded by the filter are ignored to not lock exercises forever.
+    pub fn is_locked(&self, exercise_ind: usize) -> bool {
+        self.enforce_prerequisites
+            && self.exercises[exercise_ind]
+                .prerequisites
+                .iter()
+                .any(|prerequisite| {
+                    self.exercises
+                        .iter()
+                        .any(|exercise| exercise.name == *prerequisite && !exercise.done)
+                })
+    }
+
+    /// The prerequisites of the exercise which aren't done yet.
+    pub fn pending_prerequisites(&self, exercise_ind: usize) -> Vec<&'static str> {
+        self.exercises[exercise_ind]
+            .prerequisites
+            .iter()
+            .copied()
+            .filter(|prerequisite| {
+                self.exercises
+                    .iter()
+                    .any(|exercise| exercise.name == *prerequisite && !exercise.done)
+            })
+            .collect()
+    }
 
-        if exercise_ind >= self.exercises.len() {
-            bail!(BAD_INDEX_ERR);
+    /// The message shown when trying to start a locked exercise.
+    pub fn locked_message(&self, exercise_ind: usize) -> Option<String> {
+        if !self.is_locked(exercise_ind) {
+            return None;
         }
 
-        self.current_exercise_ind = exercise_ind;
+        Some(format!(
+            "The exercise `{}` is locked until these exercises are done: {}",
+            self.exercises[exercise_ind].name,
+            quoted_list(&self.pending_prerequisites(exercise_ind)),
+        ))
+    }
 
-        self.write()
+    /// Keep the current exercise if all exercises are done.
+    pub fn set_current_exercise_to_first_pending(&mut self) -> Result<()> {
+        match (0..self.exercises.len())
+            .find(|&ind| !self.exercises[ind].done && !self.is_locked(ind))
+        {
+            Some(exercise_ind) => self.set_current_exercise_ind(exercise_ind),
+            None => Ok(()),
+        }
     }
 
-    pub fn set_current_exercise_by_name(&mut self, name: &str) -> Result<()> {
+    pub fn exercise_ind_by_name(&self, name: &str) -> Result<usize> {
         // O(N) is fine since this method is used only once until the program exits.
         // Building a hashmap would have more overhead.
-        self.current_exercise_ind = self
-            .exercises
+        self.exercises
             .iter()
             .position(|exercise| exercise.name == name)
-            .with_context(|| format!("No exercise found for '{name}'!"))?;
+            .with_context(|| format!("No exercise found for '{name}'!"))
+    }
 
-        self.write()
+    pub fn set_current_exercise_by_name(&mut self, name: &str) -> Result<()> {
+        let exercise_ind = self.exercise_ind_by_name(name)?;
+        // Rejects locked exercises.
+        self.set_current_exercise_ind(exercise_ind)
     }
 
     // Set the status of an exercise without saving. Returns `true` if the
-    // status actually changed (and thus needs saving later).
+    // status or the modification time of a done exercise changed (and thus needs saving later).
     pub fn set_status(&mut self, exercise_ind: usize, done: bool) -> Result<bool> {
         let exercise = self
             .exercises
@@ -292,34 +1179,107 @@ impl AppState {
             .context(BAD_INDEX_ERR)?;
 
         if exercise.done == done {
-            return Ok(false);
+            return Ok(done && refresh_done_mtime(exercise));
         }
 
         exercise.done = done;
         if done {
+            exercise.done_mtime = file_mtime(exercise.path);
+            record_first_done(exercise);
             self.n_done += 1;
         } else {
+            exercise.done_mtime = None;
             self.n_done -= 1;
         }
 
         Ok(true)
     }
 
-    // Set the status of an exercise to "pending" and save.
-    pub fn set_pending(&mut self, exercise_ind: usize) -> Result<()> {
-        if self.set_status(exercise_ind, false)? {
+    // Set the status of an exercise and save.
+    pub fn set_status_and_write(&mut self, exercise_ind: usize, done: bool) -> Result<()> {
+        if self.set_status(exercise_ind, done)? {
+            self.write()?;
+        }
+
+        Ok(())
+    }
+
+    // Mark all exercises as done without checking them and save.
+    // Only used for testing the final screen while developing exercises.
+    pub fn mark_all_done(&mut self) -> Result<()> {
+        for exercise in &mut self.exercises {
+            exercise.done = true;
+            exercise.done_mtime = file_mtime(exercise.path);
+            record_first_done(exercise);
+        }
+        self.n_done = self.exercises.len() as u16;
+
+        self.write()
+    }
+
+    /// Mark the done exercises whose files were modified since they were done as pending and save.
+    /// Returns the number of these exercises. They are checked again when reached.
+    pub fn mark_changed_exercises_pending(&mut self) -> Result<u16> {
+        let mut n_changed = 0;
+        let mut changed_state = false;
+
+        for exercise in &mut self.exercises {
+            if !exercise.done {
+                continue;
+            }
+
+            let mtime = file_mtime(exercise.path);
+            match exercise.done_mtime {
+                // Done with an older version or the file couldn't be read back then.
+                None => {
+                    exercise.done_mtime = mtime;
+                    changed_state |= mtime.is_some();
+                }
+                Some(done_mtime) if Some(done_mtime) != mtime => {
+                    exercise.done = false;
+                    exercise.done_mtime = None;
+                    self.n_done -= 1;
+                    n_changed += 1;
+                    changed_state = true;
+                }
+                Some(_) => (),
+            }
+        }
+
+        if changed_state {
+            self.write()?;
+        }
+
+        Ok(n_changed)
+    }
+
+    /// Save the current modification time of the exercise if it is done and passed again.
+    pub fn refresh_done_mtime(&mut self, exercise_ind: usize) -> Result<()> {
+        let exercise = self
+            .exercises
+            .get_mut(exercise_ind)
+            .context(BAD_INDEX_ERR)?;
+
+        if exercise.done && refresh_done_mtime(exercise) {
             self.write()?;
         }
 
         Ok(())
     }
 
+    // Set the status of an exercise to "pending" and save.
+    #[inline]
+    pub fn set_pending(&mut self, exercise_ind: usize) -> Result<()> {
+        self.set_status_and_write(exercise_ind, false)
+    }
+
     // Official exercises: Dump the original file from the binary.
     // Third-party exercises: Reset the exercise file with `git stash`.
-    fn reset(&self, exercise_ind: usize, path: &str) -> Result<()> {
+    fn reset(&self, exercise: &Exercise) -> Result<()> {
+        let path = exercise.path;
         if self.official_exercises {
             return EMBEDDED_FILES
-                .write_exercise_to_disk(exercise_ind, path)
+                .write_exercise_to_disk(exercise.info_ind, path)
                 .with_context(|| format!("Failed to reset the exercise {path}"));
         }
 
@@ -343,10 +1303,40 @@ impl AppState {
         Ok(())
     }
 
+    // Keep the content of the exercise file to be able to undo resetting it.
+    fn backup_before_reset(&mut self, exercise_ind: usize) {
+        let exercise = &self.exercises[exercise_ind];
+        // A missing file has nothing to restore.
+        if let Ok(content) = fs::read_to_string(exercise.path) {
+            self.reset_backups.insert(exercise.name, content);
+        }
+    }
+
+    /// Whether the last reset of the current exercise can be undone.
+    pub fn can_undo_current_reset(&self) -> bool {
+        self.reset_backups
+            .contains_key(self.current_exercise().name)
+    }
+
+    /// Restore the content of the current exercise file from before its last reset.
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo_current_reset(&mut self) -> Result<bool> {
+        let exercise = &self.exercises[self.current_exercise_ind];
+        let Some(content) = self.reset_backups.remove(exercise.name) else {
+            return Ok(false);
+        };
+
+        fs::write(exercise.path, content)
+            .with_context(|| format!("Failed to restore the exercise file {}", exercise.path))?;
+
+        Ok(true)
+    }
+
     pub fn reset_current_exercise(&mut self) -> Result<&'static str> {
+        self.backup_before_reset(self.current_exercise_ind);
         self.set_pending(self.current_exercise_ind)?;
         let exercise = self.current_exercise();
-        self.reset(self.current_exercise_ind, exercise.path)?;
+        self.reset(exercise)?;
 
         Ok(exercise.path)
     }
@@ -357,37 +1347,130 @@ impl AppState {
             bail!(BAD_INDEX_ERR);
         }
 
+        self.backup_before_reset(exercise_ind);
         self.set_pending(exercise_ind)?;
         let exercise = &self.exercises[exercise_ind];
-        self.reset(exercise_ind, exercise.path)?;
+        self.reset(exercise)?;
 
         Ok(exercise.name)
     }
 
-    // Return the index of the next pending exercise or `None` if all exercises are done.
+    /// Reset all exercises in the range of indices and mark them as pending.
+    /// Returns the number of reset exercises.
+    pub fn reset_exercises(&mut self, range: Range<usize>) -> Result<usize> {
+        if range.end > self.exercises.len() {
+            bail!(BAD_INDEX_ERR);
+        }
+
+        for exercise_ind in range.clone() {
+            self.backup_before_reset(exercise_ind);
+        }
+
+        let exercises = &self.exercises[range.clone()];
+        for exercise in exercises {
+            self.reset(exercise)?;
+        }
+
+        for exercise_ind in range.clone() {
+            self.set_status(exercise_ind, false)?;
+        }
+        self.write()?;
+
+        Ok(range.len())
+    }
+
+    /// Start over by marking all exercises as pending and forgetting the viewed hints, the peeks
+    /// and the time spent. Bookmarks, notes and the number of shown hints are kept. The latter
+    /// survives resets to not be gamed.
+    /// The exercise files are only reset if `reset_files` is `true`.
+    pub fn clear_progress(&mut self, reset_files: bool) -> Result<()> {
+        if reset_files {
+            self.reset_exercises(0..self.exercises.len())?;
+        }
+
+        for exercise in self
+            .exercises
+            .iter_mut()
+            .chain(&mut self.excluded_exercises)
+        {
+            exercise.done = false;
+            exercise.done_mtime = None;
+            exercise.hint_viewed = false;
+            exercise.solution_peeked = false;
+            exercise.time_spent = Duration::ZERO;
+            exercise.first_done_at = None;
+        }
+        self.n_done = 0;
+        self.skipped.clear();
+        self.current_exercise_ind = 0;
+
+        self.write()
+    }
+
+    // Return the index of the next pending exercise or `None` if all required exercises are done.
+    // Optional challenges are only returned if they come after the current exercise.
+    // Skipped exercises are only returned if there are no other pending exercises.
     fn next_pending_exercise_ind(&self) -> Option<usize> {
+        // Pending challenges don't prevent finishing.
+        if self
+            .exercises
+            .iter()
+            .all(|exercise| exercise.done || exercise.optional)
+        {
+            return None;
+        }
+
+        let is_candidate = |(ind, exercise): &(usize, &Exercise)| {
+            !exercise.done && !self.skipped.contains(ind) && !self.is_locked(*ind)
+        };
+
         let next_ind = self.current_exercise_ind + 1;
         self.exercises
+            .iter()
+            .enumerate()
             // If the exercise done isn't the last, search for pending exercises after it.
-            .get(next_ind..)
-            .and_then(|later_exercises| {
-                later_exercises
-                    .iter()
-                    .position(|exercise| !exercise.done)
-                    .map(|ind| next_ind + ind)
-            })
+            .skip(next_ind)
+            .find(is_candidate)
             // Search from the start.
             .or_else(|| {
                 self.exercises[..self.current_exercise_ind]
                     .iter()
-                    .position(|exercise| !exercise.done)
+                    .enumerate()
+                    .find(|candidate| is_candidate(candidate) && !candidate.1.optional)
             })
+            .map(|(ind, _)| ind)
+            .or_else(|| {
+                self.skipped.iter().copied().find(|&ind| {
+                    let exercise = &self.exercises[ind];
+                    ind != self.current_exercise_ind
+                        && !exercise.done
+                        && !exercise.optional
+                        && !self.is_locked(ind)
+                })
+            })
+    }
+
+    /// Move on to the next pending exercise without marking the current one as done.
+    /// The current exercise is queued after all other pending exercises.
+    pub fn skip_current_exercise(&mut self) -> Result<ExercisesProgress> {
+        let current_exercise_ind = self.current_exercise_ind;
+        self.skipped.retain(|ind| *ind != current_exercise_ind);
+        self.skipped.push(current_exercise_ind);
+
+        let Some(ind) = self.next_pending_exercise_ind() else {
+            return Ok(ExercisesProgress::CurrentPending);
+        };
+
+        self.set_current_exercise_ind(ind)?;
+
+        Ok(ExercisesProgress::NewPending)
     }
 
     /// Official exercises: Dump the solution file from the binary and return its path.
     /// Third-party exercises: Check if a solution file exists and return its path in that case.
+    /// Always `None` if solutions are hidden.
     pub fn current_solution_path(&self) -> Result<Option<String>> {
-        if cfg!(debug_assertions) {
+        if cfg!(debug_assertions) || self.hide_solutions {
             return Ok(None);
         }
 
@@ -395,7 +1478,7 @@ impl AppState {
 
         if self.official_exercises {
             EMBEDDED_FILES
-                .write_solution_to_disk(self.current_exercise_ind, current_exercise.name)
+                .write_solution_to_disk(current_exercise.info_ind, current_exercise.name)
                 .map(Some)
         } else {
             let sol_path = current_exercise.sol_path();
@@ -408,24 +1491,103 @@ impl AppState {
         }
     }
 
-    fn check_all_exercises_impl(&mut self, stdout: &mut StdoutLock) -> Result<Option<usize>> {
-        let term_width = terminal::size()
-            .context("Failed to get the terminal size")?
-            .0;
-        let mut progress_visualizer = CheckProgressVisualizer::build(stdout, term_width)?;
+    // The number of threads for checking exercises in parallel.
+    fn n_check_threads(&self) -> usize {
+        self.cmd_runner.jobs().map_or_else(
+            || {
+                thread::available_parallelism()
+                    .map_or(DEFAULT_CHECK_PARALLELISM, |count| count.get())
+            },
+            NonZeroUsize::get,
+        )
+    }
+
+    /// Build all exercises in parallel and return whether each of them compiles.
+    pub fn compile_all_exercises(&self) -> Result<Vec<bool>> {
+        let next_exercise_ind = AtomicUsize::new(0);
+        let n_threads = self.n_check_threads();
+
+        thread::scope(|s| {
+            let handles = (0..n_threads)
+                .map(|_| {
+                    let next_exercise_ind = &next_exercise_ind;
+                    thread::Builder::new()
+                        .spawn_scoped(s, move || {
+                            let mut results = Vec::new();
+                            loop {
+                                let exercise_ind = next_exercise_ind.fetch_add(1, Relaxed);
+                                let Some(exercise) = self.exercises.get(exercise_ind) else {
+                                    // No more exercises.
+                                    break results;
+                                };
+
+                                let compiles =
+                                    self.cmd_runner.build_exercise(exercise).unwrap_or(false);
+                                results.push((exercise_ind, compiles));
+                            }
+                        })
+                        .context("Failed to spawn a thread to compile all exercises")
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let mut compiles = vec![false; self.exercises.len()];
+            for handle in handles {
+                let Ok(results) = handle.join() else {
+                    bail!("Panic while trying to compile the exercises");
+                };
+                for (exercise_ind, exercise_compiles) in results {
+                    compiles[exercise_ind] = exercise_compiles;
+                }
+            }
+
+            Ok(compiles)
+        })
+    }
+
+    /// Check all exercises in parallel, update their status and return the result of every
+    /// exercise in order.
+    /// `on_progress` is called every time the progress of checking an exercise changes.
+    pub fn check_all_exercises_with(
+        &mut self,
+        capture_output: bool,
+        on_progress: impl FnMut(&[CheckProgress]) -> io::Result<()>,
+    ) -> Result<Vec<ExerciseCheckResult>> {
+        let results = self.run_all_exercises(capture_output, on_progress)?;
+
+        for result in &results {
+            self.set_status(result.exercise_ind, result.success)?;
+        }
+        self.write()?;
+
+        Ok(results)
+    }
 
+    /// Like `check_all_exercises_with` but without changing the status of any exercise.
+    pub fn run_all_exercises(
+        &self,
+        capture_output: bool,
+        mut on_progress: impl FnMut(&[CheckProgress]) -> io::Result<()>,
+    ) -> Result<Vec<ExerciseCheckResult>> {
         let next_exercise_ind = AtomicUsize::new(0);
         let mut progresses = vec![CheckProgress::None; self.exercises.len()];
+        let mut outputs = vec![None; self.exercises.len()];
+
+        let run_exercise = |exercise: &Exercise, cmd_runner: &dyn ExerciseRunner| {
+            let mut output = capture_output.then(|| Vec::with_capacity(OUTPUT_CAPACITY));
+            cmd_runner
+                .run_exercise(exercise, output.as_mut())
+                .map(|success| (success, output))
+        };
 
         thread::scope(|s| {
             let (exercise_progress_sender, exercise_progress_receiver) = mpsc::channel();
-            let n_threads = thread::available_parallelism()
-                .map_or(DEFAULT_CHECK_PARALLELISM, |count| count.get());
+            let n_threads = self.n_check_threads();
 
             for _ in 0..n_threads {
                 let exercise_progress_sender = exercise_progress_sender.clone();
                 let next_exercise_ind = &next_exercise_ind;
-                let slf = &self;
+                let slf = self;
+                let run_exercise = &run_exercise;
                 thread::Builder::new()
                     .spawn_scoped(s, move || loop {
                         let exercise_ind = next_exercise_ind.fetch_add(1, Relaxed);
@@ -435,21 +1597,20 @@ impl AppState {
                         };
 
                         if exercise_progress_sender
-                            .send((exercise_ind, CheckProgress::Checking))
+                            .send((exercise_ind, CheckProgress::Checking, None))
                             .is_err()
                         {
                             break;
                         };
 
-                        let success = exercise.run_exercise(None, &slf.cmd_runner);
-                        let progress = match success {
-                            Ok(true) => CheckProgress::Done,
-                            Ok(false) => CheckProgress::Pending,
-                            Err(_) => CheckProgress::None,
+                        let (progress, output) = match run_exercise(exercise, &*slf.cmd_runner) {
+                            Ok((true, output)) => (CheckProgress::Done, output),
+                            Ok((false, output)) => (CheckProgress::Pending, output),
+                            Err(_) => (CheckProgress::None, None),
                         };
 
                         if exercise_progress_sender
-                            .send((exercise_ind, progress))
+                            .send((exercise_ind, progress, output))
                             .is_err()
                         {
                             break;
@@ -461,56 +1622,79 @@ impl AppState {
             // Drop this sender to detect when the last thread is done.
             drop(exercise_progress_sender);
 
-            while let Ok((exercise_ind, progress)) = exercise_progress_receiver.recv() {
+            while let Ok((exercise_ind, progress, output)) = exercise_progress_receiver.recv() {
                 progresses[exercise_ind] = progress;
-                progress_visualizer.update(&progresses)?;
+                outputs[exercise_ind] = output;
+                on_progress(&progresses)?;
             }
 
             Ok::<_, Error>(())
         })?;
 
-        let mut first_pending_exercise_ind = None;
         for exercise_ind in 0..progresses.len() {
             match progresses[exercise_ind] {
-                CheckProgress::Done => {
-                    self.set_status(exercise_ind, true)?;
-                }
-                CheckProgress::Pending => {
-                    self.set_status(exercise_ind, false)?;
-                    if first_pending_exercise_ind.is_none() {
-                        first_pending_exercise_ind = Some(exercise_ind);
-                    }
-                }
+                CheckProgress::Done | CheckProgress::Pending => (),
                 CheckProgress::None | CheckProgress::Checking => {
                     // If we got an error while checking all exercises in parallel,
                     // it could be because we exceeded the limit of open file descriptors.
                     // Therefore, try running exercises with errors sequentially.
                     progresses[exercise_ind] = CheckProgress::Checking;
-                    progress_visualizer.update(&progresses)?;
+                    on_progress(&progresses)?;
 
-                    let exercise = &self.exercises[exercise_ind];
-                    let success = exercise.run_exercise(None, &self.cmd_runner)?;
+                    let (success, output) =
+                        run_exercise(&self.exercises[exercise_ind], &*self.cmd_runner)?;
                     if success {
                         progresses[exercise_ind] = CheckProgress::Done;
                     } else {
                         progresses[exercise_ind] = CheckProgress::Pending;
-                        if first_pending_exercise_ind.is_none() {
-                            first_pending_exercise_ind = Some(exercise_ind);
-                        }
                     }
-                    self.set_status(exercise_ind, success)?;
-                    progress_visualizer.update(&progresses)?;
+                    outputs[exercise_ind] = output;
+                    on_progress(&progresses)?;
                 }
             }
         }
 
-        self.write()?;
+        let results = outputs
+            .into_iter()
+            .zip(progresses)
+            .zip(&self.exercises)
+            .enumerate()
+            .map(
+                |(exercise_ind, ((output, progress), exercise))| ExerciseCheckResult {
+                    exercise_ind,
+                    name: exercise.name,
+                    success: matches!(progress, CheckProgress::Done),
+                    output,
+                },
+            )
+            .collect();
+
+        Ok(results)
+    }
+
+    fn check_all_exercises_impl(&mut self, stdout: &mut StdoutLock) -> Result<Vec<usize>> {
+        let term_width = term::term_width();
+        let mut progress_visualizer =
+            CheckProgressVisualizer::build(stdout, term_width, self.theme)?;
 
-        Ok(first_pending_exercise_ind)
+        let results = self
+            .check_all_exercises_with(false, |progresses| progress_visualizer.update(progresses))?;
+
+        Ok(self.pending_failures(&results))
     }
 
-    // Return the exercise index of the first pending exercise found.
-    pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<Option<usize>> {
+    // The indices of the failed exercises that prevent finishing.
+    // Failing challenges don't prevent finishing, but locked required exercises do.
+    fn pending_failures(&self, results: &[ExerciseCheckResult]) -> Vec<usize> {
+        results
+            .iter()
+            .filter(|result| !result.success && !self.exercises[result.exercise_ind].optional)
+            .map(|result| result.exercise_ind)
+            .collect()
+    }
+
+    // Return the exercise indices of all pending exercises found in order.
+    pub fn check_all_exercises_failures(&mut self, stdout: &mut StdoutLock) -> Result<Vec<usize>> {
         stdout.queue(cursor::Hide)?;
         let res = self.check_all_exercises_impl(stdout);
         stdout.queue(cursor::Show)?;
@@ -518,6 +1702,29 @@ impl AppState {
         res
     }
 
+    /// The exercise to continue on after checking all exercises or `None` if none failed.
+    /// A locked failure still prevents finishing. Its first pending prerequisite which isn't
+    /// locked is returned instead since it can't become the current exercise.
+    pub fn first_pending_of_failures(&self, failures: &[usize]) -> Option<usize> {
+        let mut ind = *failures.first()?;
+        // Prerequisites come before their exercise which ends the loop.
+        while self.is_locked(ind) {
+            let prerequisite = self.pending_prerequisites(ind)[0];
+            ind = self
+                .exercises
+                .iter()
+                .position(|exercise| exercise.name == prerequisite)?;
+        }
+
+        Some(ind)
+    }
+
+    // Return the exercise index of the first pending exercise found.
+    pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<Option<usize>> {
+        let failures = self.check_all_exercises_failures(stdout)?;
+        Ok(self.first_pending_of_failures(&failures))
+    }
+
     /// Mark the current exercise as done and move on to the next pending exercise if one exists.
     /// If all exercises are marked as done, run all of them to make sure that they are actually
     /// done. If an exercise which is marked as done fails, mark it as pending and continue on it.
@@ -528,8 +1735,11 @@ impl AppState {
         let exercise = &mut self.exercises[self.current_exercise_ind];
         if !exercise.done {
             exercise.done = true;
+            record_first_done(exercise);
             self.n_done += 1;
         }
+        // Also if it was already done but changed since then.
+        refresh_done_mtime(exercise);
 
         if let Some(ind) = self.next_pending_exercise_ind() {
             self.set_current_exercise_ind(ind)?;
@@ -555,7 +1765,8 @@ impl AppState {
 
     pub fn render_final_message(&self, stdout: &mut StdoutLock) -> Result<()> {
         clear_terminal(stdout)?;
-        stdout.write_all(FENISH_LINE.as_bytes())?;
+        let banner = self.theme.completion.final_banner.unwrap_or(FENISH_LINE);
+        stdout.write_all(banner.as_bytes())?;
 
         let final_message = self.final_message.trim_ascii();
         if !final_message.is_empty() {
@@ -567,6 +1778,19 @@ impl AppState {
     }
 }
 
+// Append a line to the exercise metadata section of the state file.
+// See `AppState::write` for the format.
+fn push_metadata_line(file_buf: &mut Vec<u8>, key: &[u8], exercise_name: &str, value: &[u8]) {
+    file_buf.extend_from_slice(key);
+    file_buf.push(b' ');
+    file_buf.extend_from_slice(exercise_name.as_bytes());
+    if !value.is_empty() {
+        file_buf.push(b' ');
+        file_buf.extend_from_slice(value);
+    }
+    file_buf.push(b'\n');
+}
+
 const BAD_INDEX_ERR: &str = "The current exercise index is higher than the number of exercises";
 const STATE_FILE_HEADER: &[u8] = b"DON'T EDIT THIS FILE!\n\n";
 const FENISH_LINE: &str = "+----------------------------------------------------+
@@ -594,34 +1818,287 @@ const FENISH_LINE: &str = "+----------------------------------------------------
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
+
+    use crate::info_file::ExerciseHint;
 
     fn dummy_exercise() -> Exercise {
         Exercise {
+            info_ind: 0,
             dir: None,
             name: "0",
             path: "exercises/0.rs",
             canonical_path: None,
             test: false,
             strict_clippy: false,
-            hint: "",
+            deny_warnings: false,
+            stdin: None,
+            pre_check: None,
+            post_check: None,
+            expected_output: None,
+            difficulty: None,
+            optional: false,
+            prerequisites: &[],
+            hints: &[""],
             done: false,
+            hint_viewed: false,
+            n_hints_shown: 0,
+            solution_peeked: false,
+            time_spent: Duration::ZERO,
+            bookmarked: false,
+            notes: Vec::new(),
+            done_mtime: None,
+            first_done_at: None,
+        }
+    }
+
+    // Passes the exercises whose names start with `pass` without compiling anything.
+    struct FakeRunner;
+
+    impl ExerciseRunner for FakeRunner {
+        fn run_exercise(&self, exercise: &Exercise, output: Option<&mut Vec<u8>>) -> Result<bool> {
+            let success = exercise.name.starts_with("pass");
+            if let Some(output) = output {
+                output.extend_from_slice(if success { b"ok" } else { b"error" });
+            }
+            Ok(success)
+        }
+
+        fn run_exercise_separated(
+            &self,
+            exercise: &Exercise,
+            stdout: &mut Vec<u8>,
+            _: &mut Vec<u8>,
+        ) -> Result<bool> {
+            self.run_exercise(exercise, Some(stdout))
+        }
+
+        fn build_exercise(&self, _: &Exercise) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn set_live_output(&self, _: bool) {}
+
+        fn jobs(&self) -> Option<NonZeroUsize> {
+            None
+        }
+
+        fn release(&self) -> bool {
+            false
+        }
+    }
+
+    fn dummy_exercise_info(name: &str) -> ExerciseInfo {
+        ExerciseInfo {
+            name: name.to_string(),
+            dir: None,
+            test: true,
+            strict_clippy: false,
+            deny_warnings: false,
+            hint: ExerciseHint::Single(String::new()),
+            hint_file: None,
+            skip_check_unsolved: false,
+            stdin: None,
+            pre_check: None,
+            post_check: None,
+            expected_output: None,
+            difficulty: None,
+            optional: false,
+            prerequisites: Vec::new(),
+            dependencies: BTreeMap::new(),
         }
     }
 
+    fn dummy_app_state(n_exercises: usize) -> AppState {
+        AppState::from_exercises(
+            (0..n_exercises).map(|_| dummy_exercise()).collect(),
+            FakeRunner,
+        )
+    }
+
     #[test]
-    fn next_pending_exercise() {
-        let mut app_state = AppState {
-            current_exercise_ind: 0,
-            exercises: vec![dummy_exercise(), dummy_exercise(), dummy_exercise()],
-            n_done: 0,
-            final_message: String::new(),
-            state_file: tempfile::tempfile().unwrap(),
-            file_buf: Vec::new(),
-            official_exercises: true,
-            cmd_runner: CmdRunner::build().unwrap(),
-            vs_code: false,
+    fn filter() {
+        assert!(matches_filter("iterators1", "iterators"));
+        assert!(matches_filter("iterators1", "tor"));
+        assert!(!matches_filter("iterators1", "Iterators"));
+        assert!(matches_filter("iterators1", "iterators*"));
+        assert!(matches_filter("iterators1", "*1"));
+        assert!(matches_filter("iterators1", "i*t*1"));
+        assert!(matches_filter("iterators1", "*"));
+        assert!(!matches_filter("iterators1", "tor*"));
+        assert!(!matches_filter("iterators1", "*tor"));
+        assert!(!matches_filter("aba", "ab*ba"));
+    }
+
+    #[test]
+    fn ignored_names() {
+        let names = parse_ignored_names("intro1\n\n# Comment\n  variables2  \n");
+        assert_eq!(names.len(), 2);
+        assert!(names.contains("intro1"));
+        assert!(names.contains("variables2"));
+    }
+
+    #[test]
+    fn group_progresses() {
+        let mut app_state = dummy_app_state(4);
+        for (exercise, dir) in
+            app_state
+                .exercises
+                .iter_mut()
+                .zip(["00_intro", "01_variables", "01_variables", "quiz"])
+        {
+            exercise.dir = Some(dir);
+        }
+        app_state.exercises[1].done = true;
+
+        let groups = app_state
+            .group_progresses()
+            .into_iter()
+            .map(|group| (group.name, group.n_done, group.n_exercises))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            groups,
+            [("intro", 0, 1), ("variables", 1, 2), ("quiz", 0, 1)],
+        );
+    }
+
+    #[test]
+    fn shuffle() {
+        let dirs = [
+            Some("a"),
+            Some("a"),
+            Some(QUIZZES_DIR),
+            Some("b"),
+            None,
+            None,
+            Some("c"),
+            Some("c"),
+        ];
+        let names = ["a1", "a2", "quiz1", "b1", "x", "y", "c1", "c2"];
+        let exercises = || {
+            dirs.into_iter()
+                .zip(names)
+                .map(|(dir, name)| Exercise {
+                    dir,
+                    name,
+                    prerequisites: if name == "c1" { &["b1"] } else { &[] },
+                    ..dummy_exercise()
+                })
+                .collect::<Vec<_>>()
+        };
+        let shuffled_names = |seed| {
+            let mut exercises = exercises();
+            shuffle_groups(&mut exercises, seed);
+            exercises
+                .iter()
+                .map(|exercise| exercise.name)
+                .collect::<Vec<_>>()
         };
 
+        let shuffled = shuffled_names(42);
+        assert_eq!(shuffled, shuffled_names(42));
+        let mut sorted = shuffled.clone();
+        sorted.sort_unstable();
+        let mut expected = names.to_vec();
+        expected.sort_unstable();
+        assert_eq!(sorted, expected);
+
+        // Groups stay together in their order.
+        let a1 = shuffled.iter().position(|name| *name == "a1").unwrap();
+        assert_eq!(shuffled[a1 + 1], "a2");
+        let c1 = shuffled.iter().position(|name| *name == "c1").unwrap();
+        assert_eq!(shuffled[c1 + 1], "c2");
+
+        for seed in 0..64 {
+            let shuffled = shuffled_names(seed);
+            let position = |name| shuffled.iter().position(|other| *other == name).unwrap();
+            // The quiz stays after its group.
+            assert_eq!(position("quiz1"), position("a2") + 1);
+            // Prerequisites come first.
+            assert!(position("b1") < position("c1"));
+        }
+    }
+
+    #[test]
+    fn notes() {
+        let mut app_state = dummy_app_state(2);
+        app_state.file_buf.extend_from_slice(STATE_FILE_HEADER);
+        app_state.exercises[1].name = "1";
+        app_state.current_exercise_ind = 1;
+
+        app_state.add_current_note("shadowing\nvs mut ").unwrap();
+        app_state.add_current_note("  ").unwrap();
+        app_state.add_current_note("second").unwrap();
+        assert_eq!(app_state.exercises[1].notes, ["shadowing vs mut", "second"]);
+
+        let mut exercises = [dummy_exercise(), dummy_exercise()];
+        exercises[1].name = "1";
+        read_state(&app_state.file_buf, &mut exercises).unwrap();
+        assert!(exercises[0].notes.is_empty());
+        assert_eq!(exercises[1].notes, ["shadowing vs mut", "second"]);
+
+        app_state.clear_current_notes().unwrap();
+        assert!(app_state.exercises[1].notes.is_empty());
+    }
+
+    #[test]
+    fn stats() {
+        let mut app_state = dummy_app_state(5);
+        for (exercise, done) in app_state
+            .exercises
+            .iter_mut()
+            .zip([true, false, true, true, false])
+        {
+            exercise.done = done;
+            exercise.time_spent = Duration::from_secs(10);
+        }
+        app_state.exercises[2].hint_viewed = true;
+        app_state.exercises[2].n_hints_shown = 2;
+        app_state.exercises[4].solution_peeked = true;
+        app_state.n_done = 3;
+
+        app_state.current_exercise_ind = 4;
+        let stats = app_state.stats();
+        assert_eq!(stats.n_exercises, 5);
+        assert_eq!(stats.n_done, 3);
+        assert_eq!(stats.streak, 2);
+        assert_eq!(stats.time_spent, Duration::from_secs(50));
+        assert_eq!(stats.n_hints_viewed, 1);
+        assert_eq!(stats.n_hints_shown, 2);
+        assert_eq!(stats.n_solutions_peeked, 1);
+
+        app_state.current_exercise_ind = 1;
+        assert_eq!(app_state.stats().streak, 1);
+
+        app_state.exercises[3].done = false;
+        app_state.current_exercise_ind = 4;
+        assert_eq!(app_state.stats().streak, 0);
+    }
+
+    #[test]
+    fn test_daily_done() {
+        let mut app_state = dummy_app_state(4);
+        let today = 20_000;
+        assert!(daily_done(&app_state.exercises, today).is_none());
+
+        app_state.exercises[0].first_done_at = Some(today * SECS_PER_DAY + 10);
+        assert!(daily_done(&app_state.exercises, today).is_none());
+
+        app_state.exercises[1].first_done_at = Some(today * SECS_PER_DAY + 20);
+        app_state.exercises[2].first_done_at = Some((today - 2) * SECS_PER_DAY);
+        // Older than the trend.
+        app_state.exercises[3].first_done_at = Some((today - 100) * SECS_PER_DAY);
+        let daily_done = daily_done(&app_state.exercises, today).unwrap();
+        assert_eq!(daily_done.len(), TREND_DAYS);
+        assert_eq!(daily_done[TREND_DAYS - 1], 2);
+        assert_eq!(daily_done[TREND_DAYS - 3], 1);
+        assert_eq!(daily_done.iter().sum::<u16>(), 3);
+    }
+
+    #[test]
+    fn next_pending_exercise() {
+        let mut app_state = dummy_app_state(3);
+
         let mut assert = |done: [bool; 3], expected: [Option<usize>; 3]| {
             for (exercise, done) in app_state.exercises.iter_mut().zip(done) {
                 exercise.done = done;
@@ -645,4 +2122,207 @@ mod tests {
         assert([false, true, false], [Some(2), Some(2), Some(0)]);
         assert([false, false, true], [Some(1), Some(0), Some(0)]);
     }
+
+    #[test]
+    fn next_pending_exercise_optional() {
+        let mut app_state = dummy_app_state(3);
+        app_state.exercises[1].optional = true;
+
+        let mut assert = |done: [bool; 3], expected: [Option<usize>; 3]| {
+            for (exercise, done) in app_state.exercises.iter_mut().zip(done) {
+                exercise.done = done;
+            }
+            for (ind, expected) in expected.into_iter().enumerate() {
+                app_state.current_exercise_ind = ind;
+                assert_eq!(
+                    app_state.next_pending_exercise_ind(),
+                    expected,
+                    "done={done:?}, ind={ind}",
+                );
+            }
+        };
+
+        // Only the optional exercise is pending.
+        assert([true, false, true], [None, None, None]);
+        // The optional exercise isn't returned when searching from the start.
+        assert([true, false, false], [Some(1), Some(2), None]);
+        assert([false, false, true], [Some(1), Some(0), Some(0)]);
+
+        assert_eq!(app_state.core_progress(), (1, 2));
+        assert_eq!(app_state.challenge_progress(), (0, 1));
+    }
+
+    #[test]
+    fn in_memory() {
+        let mut app_state = AppState::in_memory(
+            vec![dummy_exercise_info("a"), dummy_exercise_info("b")],
+            FakeRunner,
+        );
+        assert_eq!(app_state.current_exercise().path, "exercises/a.rs");
+
+        // The state is only written to the buffer.
+        app_state.set_status_and_write(1, true).unwrap();
+        assert_eq!(app_state.n_done(), 1);
+        // Followed by the metadata like the time of the first completion.
+        let done_list = b"a\n\nb\n\nfirst_done_at b ";
+        assert!(app_state
+            .file_buf
+            .windows(done_list.len())
+            .any(|window| window == done_list));
+    }
+
+    #[test]
+    fn first_pending() {
+        let mut app_state = AppState::in_memory(
+            vec![dummy_exercise_info("a"), dummy_exercise_info("b")],
+            FakeRunner,
+        );
+        app_state.set_status_and_write(0, true).unwrap();
+        app_state.set_current_exercise_to_first_pending().unwrap();
+        assert_eq!(app_state.current_exercise_ind(), 1);
+
+        // All done
+        app_state.set_status_and_write(1, true).unwrap();
+        app_state.set_current_exercise_ind(0).unwrap();
+        app_state.set_current_exercise_to_first_pending().unwrap();
+        assert_eq!(app_state.current_exercise_ind(), 0);
+    }
+
+    #[test]
+    fn clear_progress() {
+        let mut app_state = AppState::in_memory(
+            vec![dummy_exercise_info("a"), dummy_exercise_info("b")],
+            FakeRunner,
+        );
+        app_state.set_status_and_write(0, true).unwrap();
+        app_state.set_current_exercise_ind(1).unwrap();
+        app_state.set_current_hint_viewed().unwrap();
+        let n_hints_shown = app_state.current_exercise().n_hints_shown;
+        assert!(n_hints_shown > 0);
+
+        app_state.clear_progress(false).unwrap();
+        assert_eq!(app_state.n_done(), 0);
+        assert_eq!(app_state.current_exercise_ind(), 0);
+        assert!(app_state
+            .exercises()
+            .iter()
+            .all(|exercise| !exercise.done && !exercise.hint_viewed));
+        assert_eq!(app_state.exercises()[1].n_hints_shown, n_hints_shown);
+    }
+
+    #[test]
+    fn prerequisites() {
+        let mut b = dummy_exercise_info("b");
+        b.prerequisites.push("a".to_string());
+        let mut app_state = AppState::in_memory(vec![dummy_exercise_info("a"), b], FakeRunner);
+
+        // Free roam by default.
+        assert!(!app_state.is_locked(1));
+        app_state.set_current_exercise_ind(1).unwrap();
+
+        app_state.enforce_prerequisites().unwrap();
+        assert_eq!(app_state.current_exercise_ind(), 0);
+        assert!(app_state.set_current_exercise_ind(1).is_err());
+
+        assert!(app_state.set_current_exercise_by_name("b").is_err());
+        app_state.set_status_and_write(0, true).unwrap();
+        app_state.set_current_exercise_ind(1).unwrap();
+
+        // Only earlier exercises can be prerequisites.
+        let mut a = dummy_exercise_info("a");
+        a.prerequisites.push("b".to_string());
+        assert!(exercises_from_infos(vec![a, dummy_exercise_info("b")]).is_err());
+    }
+
+    #[test]
+    fn check_all_exercises_locked_failure() {
+        let mut challenge = dummy_exercise_info("fail_challenge");
+        challenge.optional = true;
+        let mut required = dummy_exercise_info("fail_required");
+        required.prerequisites.push("fail_challenge".to_string());
+        let mut app_state = AppState::in_memory(
+            vec![dummy_exercise_info("pass"), challenge, required],
+            FakeRunner,
+        );
+        app_state.enforce_prerequisites().unwrap();
+
+        let results = app_state
+            .check_all_exercises_with(false, |_| Ok(()))
+            .unwrap();
+        let failures = app_state.pending_failures(&results);
+        assert_eq!(failures, [2]);
+        assert!(app_state.is_locked(2));
+        // Continue on the prerequisite instead of finishing.
+        assert_eq!(app_state.first_pending_of_failures(&failures), Some(1));
+        assert_eq!(app_state.first_pending_of_failures(&[]), None);
+    }
+
+    #[test]
+    fn check_all_exercises() {
+        let mut app_state = AppState::in_memory(
+            vec![dummy_exercise_info("pass1"), dummy_exercise_info("fail1")],
+            FakeRunner,
+        );
+
+        let results = app_state
+            .check_all_exercises_with(true, |_| Ok(()))
+            .unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[0].output.as_deref(), Some(&b"ok"[..]));
+        assert!(!results[1].success);
+        assert_eq!(app_state.n_done(), 1);
+        assert!(app_state.exercises[0].done);
+    }
+
+    #[test]
+    fn skip_exercise() {
+        let mut app_state = dummy_app_state(4);
+        app_state.exercises[3].done = true;
+
+        app_state.current_exercise_ind = 1;
+        assert!(matches!(
+            app_state.skip_current_exercise().unwrap(),
+            ExercisesProgress::NewPending,
+        ));
+        assert_eq!(app_state.current_exercise_ind, 2);
+
+        // The skipped exercise comes after all other pending exercises.
+        assert_eq!(app_state.next_pending_exercise_ind(), Some(0));
+        app_state.exercises[0].done = true;
+        assert_eq!(app_state.next_pending_exercise_ind(), Some(1));
+
+        // Skipping the last pending non-skipped exercise goes back to the skipped one.
+        assert!(matches!(
+            app_state.skip_current_exercise().unwrap(),
+            ExercisesProgress::NewPending,
+        ));
+        assert_eq!(app_state.current_exercise_ind, 1);
+        assert_eq!(app_state.skipped, [1, 2]);
+
+        // Nothing else to skip to.
+        app_state.exercises[2].done = true;
+        assert!(matches!(
+            app_state.skip_current_exercise().unwrap(),
+            ExercisesProgress::CurrentPending,
+        ));
+        assert_eq!(app_state.current_exercise_ind, 1);
+    }
+
+    #[test]
+    fn hint_file_warning() {
+        let hint_file = env::temp_dir().join(format!("rustlings-hint-{}.txt", std::process::id()));
+        fs::write(&hint_file, "From the hint file\n").unwrap();
+        let hint_file = hint_file.to_string_lossy().into_owned();
+
+        let mut exercise_info = dummy_exercise_info("a");
+        exercise_info.hint = ExerciseHint::Single("hint".to_string());
+        exercise_info.hint_file = Some(hint_file.clone());
+
+        let res = exercises_from_infos(vec![exercise_info]);
+        let _ = fs::remove_file(&hint_file);
+        let (exercises, warnings) = res.unwrap();
+        assert_eq!(exercises[0].hints, ["From the hint file"]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(&format!("`{hint_file}`")));
+    }
 }
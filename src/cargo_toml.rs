@@ -1,7 +1,13 @@
-use anyhow::{Context, Result};
-use std::path::Path;
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
 
-use crate::{exercise::RunnableExercise, info_file::ExerciseInfo};
+use crate::{
+    exercise::RunnableExercise,
+    info_file::{Dependency, ExerciseInfo},
+};
 
 /// Initial capacity of the bins buffer.
 pub const BINS_BUFFER_CAPACITY: usize = 1 << 14;
@@ -65,7 +71,125 @@ pub fn append_bins(
     }
 }
 
+/// Return the start and end index of the content of the `[dependencies]` table if it exists.
+/// The content ends before the next table or at the end of the file.
+pub fn dependencies_start_end_ind(cargo_toml: &str) -> Option<(usize, usize)> {
+    let start_ind = if cargo_toml.starts_with("[dependencies]\n") {
+        15
+    } else {
+        cargo_toml.find("\n[dependencies]\n")? + 16
+    };
+    let end_ind = cargo_toml[start_ind..]
+        .find("\n[")
+        .map_or(cargo_toml.len(), |ind| start_ind + ind + 1);
+
+    Some((start_ind, end_ind))
+}
+
+/// Generate the content of the `[dependencies]` table from the dependencies of all exercises.
+/// Returns `None` if no exercise has dependencies.
+/// Fails if two exercises depend on different versions of the same crate.
+pub fn dependencies(exercise_infos: &[ExerciseInfo]) -> Result<Option<Vec<u8>>> {
+    // Crate names to their version, merged features and the first exercise depending on it.
+    let mut merged = BTreeMap::<&str, (&str, BTreeSet<&str>, &str)>::new();
+    for exercise_info in exercise_infos {
+        for (name, dependency) in &exercise_info.dependencies {
+            let (version, features) = match dependency {
+                Dependency::Version(version) => (version, &[][..]),
+                Dependency::Detailed { version, features } => (version, features.as_slice()),
+            };
+
+            let entry = merged
+                .entry(name)
+                .or_insert_with(|| (version, BTreeSet::new(), &exercise_info.name));
+            if entry.0 != version {
+                bail!(
+                    "The exercises `{}` and `{}` depend on different versions of the crate `{name}`",
+                    entry.2,
+                    exercise_info.name,
+                );
+            }
+            entry.1.extend(features.iter().map(String::as_str));
+        }
+    }
+
+    if merged.is_empty() {
+        return Ok(None);
+    }
+
+    let mut buf = Vec::with_capacity(64 * merged.len());
+    for (name, (version, features, _)) in merged {
+        buf.extend_from_slice(name.as_bytes());
+        buf.extend_from_slice(b" = { version = \"");
+        buf.extend_from_slice(version.as_bytes());
+        buf.push(b'"');
+        if !features.is_empty() {
+            buf.extend_from_slice(b", features = [");
+            for (ind, feature) in features.into_iter().enumerate() {
+                if ind > 0 {
+                    buf.extend_from_slice(b", ");
+                }
+                buf.push(b'"');
+                buf.extend_from_slice(feature.as_bytes());
+                buf.push(b'"');
+            }
+            buf.push(b']');
+        }
+        buf.extend_from_slice(b" }\n");
+    }
+    buf.push(b'\n');
+
+    Ok(Some(buf))
+}
+
+// The crate name of a line like `serde = "1"` in the `[dependencies]` table.
+fn dependency_name(line: &str) -> Option<&str> {
+    let (name, _) = line.split_once('=')?;
+    let name = name.trim().trim_matches('"');
+    (!name.is_empty() && !name.starts_with('#')).then_some(name)
+}
+
+// Merge the generated dependencies into the content of an existing `[dependencies]` table.
+// Entries of the same crates are replaced and new ones are appended.
+// Other lines like manually added dependencies and comments are kept.
+fn merge_dependencies(table: &str, generated: &str) -> String {
+    let mut generated = generated
+        .lines()
+        .filter_map(|line| Some((dependency_name(line)?, line, false)))
+        .collect::<Vec<_>>();
+
+    let content = table.trim_end_matches('\n');
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let replacement = dependency_name(line).and_then(|name| {
+            generated
+                .iter_mut()
+                .find(|(generated_name, _, _)| *generated_name == name)
+        });
+        match replacement {
+            Some((_, generated_line, used)) => {
+                *used = true;
+                lines.push(*generated_line);
+            }
+            None => lines.push(line),
+        }
+    }
+    lines.extend(
+        generated
+            .iter()
+            .filter(|(_, _, used)| !used)
+            .map(|(_, line, _)| *line),
+    );
+
+    let mut merged = lines.join("\n");
+    let tail = &table[content.len()..];
+    merged.push_str(if tail.is_empty() { "\n" } else { tail });
+    merged
+}
+
 /// Update the `bin` list and leave everything else unchanged.
+/// If any exercise has dependencies, they are merged into the `[dependencies]` table.
+/// Dependencies of other crates are kept.
 pub fn updated_cargo_toml(
     exercise_infos: &[ExerciseInfo],
     current_cargo_toml: &str,
@@ -74,19 +198,45 @@ pub fn updated_cargo_toml(
     let (bins_start_ind, bins_end_ind) = bins_start_end_ind(current_cargo_toml)?;
 
     let mut updated_cargo_toml = Vec::with_capacity(BINS_BUFFER_CAPACITY);
-    updated_cargo_toml.extend_from_slice(current_cargo_toml[..bins_start_ind].as_bytes());
+    updated_cargo_toml.extend_from_slice(&current_cargo_toml.as_bytes()[..bins_start_ind]);
     append_bins(
         &mut updated_cargo_toml,
         exercise_infos,
         exercise_path_prefix,
     );
-    updated_cargo_toml.extend_from_slice(current_cargo_toml[bins_end_ind..].as_bytes());
+    updated_cargo_toml.extend_from_slice(&current_cargo_toml.as_bytes()[bins_end_ind..]);
+
+    let Some(dependencies) = dependencies(exercise_infos)? else {
+        return Ok(updated_cargo_toml);
+    };
+
+    // Only built from the string slices above.
+    let updated_str = String::from_utf8_lossy(&updated_cargo_toml).into_owned();
+    if updated_str.starts_with("[dependencies.") || updated_str.contains("\n[dependencies.") {
+        bail!("Tables like `[dependencies.NAME]` in `Cargo.toml` aren't supported if exercises have dependencies in `info.toml`. Use inline tables like `NAME = {{ version = \"1\" }}` in the `[dependencies]` table instead");
+    }
+
+    if let Some((start_ind, end_ind)) = dependencies_start_end_ind(&updated_str) {
+        let merged = merge_dependencies(
+            &updated_str[start_ind..end_ind],
+            &String::from_utf8_lossy(&dependencies),
+        );
+        updated_cargo_toml.splice(start_ind..end_ind, merged.into_bytes());
+    } else {
+        if !updated_cargo_toml.ends_with(b"\n") {
+            updated_cargo_toml.push(b'\n');
+        }
+        updated_cargo_toml.extend_from_slice(b"\n[dependencies]\n");
+        updated_cargo_toml.extend_from_slice(&dependencies);
+    }
 
     Ok(updated_cargo_toml)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::info_file::ExerciseHint;
+
     use super::*;
 
     #[test]
@@ -110,16 +260,36 @@ mod tests {
                 dir: None,
                 test: true,
                 strict_clippy: true,
-                hint: String::new(),
+                deny_warnings: false,
+                hint: ExerciseHint::Single(String::new()),
+                hint_file: None,
                 skip_check_unsolved: false,
+                stdin: None,
+                pre_check: None,
+                post_check: None,
+                expected_output: None,
+                difficulty: None,
+                optional: false,
+                prerequisites: Vec::new(),
+                dependencies: BTreeMap::new(),
             },
             ExerciseInfo {
                 name: String::from("2"),
                 dir: Some(String::from("d")),
                 test: false,
                 strict_clippy: false,
-                hint: String::new(),
+                deny_warnings: false,
+                hint: ExerciseHint::Single(String::new()),
+                hint_file: None,
                 skip_check_unsolved: false,
+                stdin: None,
+                pre_check: None,
+                post_check: None,
+                expected_output: None,
+                difficulty: None,
+                optional: false,
+                prerequisites: Vec::new(),
+                dependencies: BTreeMap::new(),
             },
         ];
 
@@ -143,4 +313,84 @@ bin = [
 123"#,
         );
     }
+
+    #[test]
+    fn test_dependencies() {
+        let exercise_info = |name: &str, dependencies: &[(&str, Dependency)]| ExerciseInfo {
+            name: name.to_string(),
+            dir: None,
+            test: true,
+            strict_clippy: false,
+            deny_warnings: false,
+            hint: ExerciseHint::Single(String::new()),
+            hint_file: None,
+            skip_check_unsolved: false,
+            stdin: None,
+            pre_check: None,
+            post_check: None,
+            expected_output: None,
+            difficulty: None,
+            optional: false,
+            prerequisites: Vec::new(),
+            dependencies: dependencies
+                .iter()
+                .map(|(name, dependency)| (name.to_string(), dependency.clone()))
+                .collect(),
+        };
+        let detailed = |features: &[&str]| Dependency::Detailed {
+            version: String::from("1"),
+            features: features.iter().map(|feature| feature.to_string()).collect(),
+        };
+
+        let mut exercise_infos = vec![
+            exercise_info("1", &[("serde", detailed(&["derive"]))]),
+            exercise_info("2", &[]),
+            exercise_info(
+                "3",
+                &[
+                    ("serde", detailed(&["rc"])),
+                    ("rand", Dependency::Version(String::from("0.8"))),
+                ],
+            ),
+        ];
+        let expected = r#"rand = { version = "0.8" }
+serde = { version = "1", features = ["derive", "rc"] }
+
+"#;
+        assert_eq!(
+            dependencies(&exercise_infos).unwrap().unwrap(),
+            expected.as_bytes(),
+        );
+
+        // The table is appended if it doesn't exist and replaced otherwise.
+        let updated = updated_cargo_toml(&exercise_infos, "bin = []\n", b"").unwrap();
+        let updated = String::from_utf8(updated).unwrap();
+        assert!(updated.ends_with(&format!("]\n\n[dependencies]\n{expected}")));
+        assert_eq!(
+            updated_cargo_toml(&exercise_infos, &updated, b"").unwrap(),
+            updated.as_bytes(),
+        );
+
+        // Without dependencies, the table isn't touched.
+        let cargo_toml = "bin = []\n\n[dependencies]\nx = \"1\"\n";
+        assert!(dependencies(&exercise_infos[1..2]).unwrap().is_none());
+        assert_eq!(
+            updated_cargo_toml(&exercise_infos[1..2], cargo_toml, b"").unwrap(),
+            b"bin = [\n  { name = \"2\", path = \"exercises/2.rs\" },\n]\n\n[dependencies]\nx = \"1\"\n",
+        );
+
+        // Manually added dependencies are kept.
+        let cargo_toml =
+            "bin = []\n\n[dependencies]\n# Comment\nx = \"1\"\nserde = \"0.1\"\n\n[profile.dev]\n";
+        let updated = updated_cargo_toml(&exercise_infos, cargo_toml, b"").unwrap();
+        assert!(String::from_utf8(updated).unwrap().ends_with(
+            "[dependencies]\n# Comment\nx = \"1\"\nserde = { version = \"1\", features = [\"derive\", \"rc\"] }\nrand = { version = \"0.8\" }\n\n[profile.dev]\n",
+        ));
+
+        let cargo_toml = "bin = []\n\n[dependencies.x]\nversion = \"1\"\n";
+        assert!(updated_cargo_toml(&exercise_infos, cargo_toml, b"").is_err());
+
+        exercise_infos.push(exercise_info("4", &[("rand", detailed(&[]))]));
+        assert!(dependencies(&exercise_infos).is_err());
+    }
 }
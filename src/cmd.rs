@@ -1,49 +1,373 @@
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, Context, Error, Result};
 use serde::Deserialize;
 use std::{
-    io::Read,
+    env,
+    io::{self, ErrorKind, Read, Write},
+    num::NonZeroUsize,
     path::PathBuf,
     process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering::Relaxed},
+        mpsc::{channel, RecvTimeoutError},
+        Arc, Mutex, PoisonError,
+    },
+    thread,
+    time::Duration,
 };
 
+use crate::{
+    exercise::{Exercise, RunnableExercise},
+    term::stop_spinner,
+};
+
+/// The default time limit for running an exercise or its tests.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// The default maximum number of captured output bytes of a command.
+pub const DEFAULT_MAX_OUTPUT_LEN: usize = 1 << 20;
+
+// Kill the process and all of its children (e.g. the test binary run by `cargo test`).
+fn kill_process_tree(pid: u32) {
+    #[cfg(not(windows))]
+    if let Some(pid) = rustix::process::Pid::from_raw(pid as i32) {
+        // The process is the leader of its own process group.
+        let _ = rustix::process::kill_process_group(pid, rustix::process::Signal::Kill);
+    }
+
+    #[cfg(windows)]
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+// Limits of running a command.
+#[derive(Clone, Copy)]
+struct CmdLimits {
+    max_output_len: Option<usize>,
+    timeout: Option<Duration>,
+}
+
+// Append the bytes to the output while keeping it below `max_output_len` since `output_start`.
+// Returns the number of dropped bytes.
+fn append_limited(
+    output: &mut Vec<u8>,
+    output_start: usize,
+    bytes: &[u8],
+    max_output_len: Option<usize>,
+) -> usize {
+    let n_kept = max_output_len.map_or(bytes.len(), |max_output_len| {
+        max_output_len
+            .saturating_sub(output.len() - output_start)
+            .min(bytes.len())
+    });
+    output.extend_from_slice(&bytes[..n_kept]);
+    bytes.len() - n_kept
+}
+
 /// Run a command with a description for a possible error and append the merged stdout and stderr.
+/// If `stderr_output` is set, stderr is appended to it instead of being merged into `output`.
+/// If `live` is true, the output is also written to stdout while it arrives.
+/// The output beyond `max_output_len` bytes is dropped and replaced by a notice.
+/// `stdin` is written to the standard input of the command if provided.
+/// The command is killed if it runs longer than `timeout` which counts as a failure.
 /// The boolean in the returned `Result` is true if the command's exit status is success.
-fn run_cmd(mut cmd: Command, description: &str, output: Option<&mut Vec<u8>>) -> Result<bool> {
-    let spawn = |mut cmd: Command| {
-        // NOTE: The closure drops `cmd` which prevents a pipe deadlock.
-        cmd.stdin(Stdio::null())
+fn run_cmd(
+    cmd: &mut Command,
+    description: &str,
+    stdin: Option<&str>,
+    mut output: Option<&mut Vec<u8>>,
+    stderr_output: Option<&mut Vec<u8>>,
+    live: bool,
+    CmdLimits {
+        max_output_len,
+        timeout,
+    }: CmdLimits,
+) -> Result<bool> {
+    // Allows killing the command with all processes that it started.
+    #[cfg(not(windows))]
+    if timeout.is_some() {
+        std::os::unix::process::CommandExt::process_group(cmd, 0);
+    }
+
+    let timed_out = Arc::new(AtomicBool::new(false));
+    // Dropping the sender stops the watchdog thread.
+    let (done_sender, done_receiver) = channel::<()>();
+
+    let spawn = |cmd: &mut Command| {
+        let mut handle = cmd
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
             .spawn()
-            .with_context(|| format!("Failed to run the command `{description}`"))
+            .with_context(|| format!("Failed to run the command `{description}`"))?;
+        // NOTE: Dropping the pipe writers held by `cmd` prevents a pipe deadlock.
+        cmd.stdout(Stdio::null()).stderr(Stdio::null());
+
+        // Write the input in a separate thread to not block on a full output pipe.
+        if let (Some(stdin), Some(mut child_stdin)) = (stdin, handle.stdin.take()) {
+            let stdin = stdin.as_bytes().to_vec();
+            thread::Builder::new()
+                // The command doesn't have to read all of the input.
+                .spawn(move || child_stdin.write_all(&stdin))
+                .with_context(|| {
+                    format!("Failed to spawn a thread to write the input of `{description}`")
+                })?;
+        }
+
+        if let Some(timeout) = timeout {
+            let pid = handle.id();
+            let timed_out = Arc::clone(&timed_out);
+            thread::Builder::new()
+                .spawn(move || {
+                    if done_receiver.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                        timed_out.store(true, Relaxed);
+                        kill_process_tree(pid);
+                    }
+                })
+                .with_context(|| {
+                    format!("Failed to spawn a thread to watch the timeout of `{description}`")
+                })?;
+        }
+
+        Ok::<_, Error>(handle)
     };
 
-    let mut handle = if let Some(output) = output {
+    let mut handle = if let Some(output) = output.as_deref_mut() {
         let (mut reader, writer) = os_pipe::pipe().with_context(|| {
             format!("Failed to create a pipe to run the command `{description}``")
         })?;
 
-        let writer_clone = writer.try_clone().with_context(|| {
-            format!("Failed to clone the pipe writer for the command `{description}`")
-        })?;
-
-        cmd.stdout(writer_clone).stderr(writer);
+        let stderr_reader = if stderr_output.is_some() {
+            let (stderr_reader, stderr_writer) = os_pipe::pipe().with_context(|| {
+                format!("Failed to create a pipe for the stderr of the command `{description}`")
+            })?;
+            cmd.stdout(writer).stderr(stderr_writer);
+            Some(stderr_reader)
+        } else {
+            let writer_clone = writer.try_clone().with_context(|| {
+                format!("Failed to clone the pipe writer for the command `{description}`")
+            })?;
+            cmd.stdout(writer_clone).stderr(writer);
+            None
+        };
         let handle = spawn(cmd)?;
 
-        reader
-            .read_to_end(output)
-            .with_context(|| format!("Failed to read the output of the command `{description}`"))?;
+        // Read stderr in a separate thread to not block the command on a full pipe.
+        let stderr_thread = stderr_reader
+            .map(|mut stderr_reader| {
+                thread::Builder::new()
+                    .spawn(move || {
+                        let mut stderr = Vec::new();
+                        stderr_reader.read_to_end(&mut stderr).map(|_| stderr)
+                    })
+                    .with_context(|| {
+                        format!("Failed to spawn a thread to read the stderr of `{description}`")
+                    })
+            })
+            .transpose()?;
+
+        let output_start = output.len();
+        let mut n_omitted = 0;
+        let mut stdout = io::stdout();
+        let mut chunk = [0; 4096];
+        loop {
+            let n = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    return Err(Error::from(e).context(format!(
+                        "Failed to read the output of the command `{description}`"
+                    )));
+                }
+            };
+
+            // Reading continues after reaching the limit to not block the command on a full pipe.
+            let n_dropped = append_limited(output, output_start, &chunk[..n], max_output_len);
+            n_omitted += n_dropped;
+            if n_dropped == n {
+                continue;
+            }
 
+            let chunk = &chunk[..n - n_dropped];
+            if live {
+                // The spinner would overwrite the output.
+                stop_spinner();
+                // Showing the output live is only a convenience, the buffer has the full output.
+                let _ = stdout.write_all(chunk).and_then(|()| stdout.flush());
+            }
+        }
+
+        if n_omitted > 0 {
+            write!(output, "\n… output truncated ({n_omitted} bytes omitted)")?;
+        }
         output.push(b'\n');
 
+        if let (Some(stderr_thread), Some(stderr_output)) = (stderr_thread, stderr_output) {
+            let stderr = stderr_thread
+                .join()
+                .map_err(|_| {
+                    Error::msg(format!(
+                        "The thread reading the stderr of `{description}` panicked"
+                    ))
+                })?
+                .with_context(|| {
+                    format!("Failed to read the stderr of the command `{description}`")
+                })?;
+
+            let stderr_start = stderr_output.len();
+            let n_omitted = append_limited(stderr_output, stderr_start, &stderr, max_output_len);
+            if n_omitted > 0 {
+                write!(
+                    stderr_output,
+                    "\n… output truncated ({n_omitted} bytes omitted)"
+                )?;
+            }
+        }
+
         handle
     } else {
         cmd.stdout(Stdio::null()).stderr(Stdio::null());
         spawn(cmd)?
     };
 
-    handle
+    let status = handle
         .wait()
-        .with_context(|| format!("Failed to wait on the command `{description}` to exit"))
-        .map(|status| status.success())
+        .with_context(|| format!("Failed to wait on the command `{description}` to exit"))?;
+    drop(done_sender);
+
+    if timed_out.load(Relaxed) {
+        if let (Some(output), Some(timeout)) = (output, timeout) {
+            writeln!(
+                output,
+                "The command `{description}` was stopped after the timeout of {} seconds. Is there an infinite loop?",
+                timeout.as_secs(),
+            )?;
+        }
+
+        return Ok(false);
+    }
+
+    Ok(status.success())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+// Cargo failures caused by the environment (e.g. another Cargo process holding a lock)
+// and not by the code of the exercise.
+const TRANSIENT_FAILURE_MARKERS: &[&[u8]] = &[
+    b"Blocking waiting for file lock",
+    b"failed to acquire package cache lock",
+    b"Resource temporarily unavailable",
+    b"Text file busy",
+];
+
+// Failures caused by the code of the exercise. These are never retried.
+const CODE_FAILURE_MARKERS: &[&[u8]] = &[
+    b"could not compile",
+    b"error[E",
+    b"panicked at",
+    b"test failed",
+    b"test result: FAILED",
+    b"Is there an infinite loop?",
+];
+
+/// Whether the output of a failed Cargo command only indicates a failure worth retrying.
+fn is_transient_failure(output: &[u8]) -> bool {
+    TRANSIENT_FAILURE_MARKERS
+        .iter()
+        .any(|marker| contains(output, marker))
+        && !CODE_FAILURE_MARKERS
+            .iter()
+            .any(|marker| contains(output, marker))
+}
+
+// The clipboard commands of the platform with their arguments in the order they are tried.
+// Wayland comes before X11 since XWayland is often also available on Wayland.
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+    &[("pbcopy", &[])]
+} else if cfg!(windows) {
+    &[("clip", &[])]
+} else {
+    &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ]
+};
+
+/// The names of the clipboard commands tried by `copy_to_clipboard` (e.g. to name them for the
+/// user if none of them is installed).
+pub fn clipboard_command_names() -> Vec<&'static str> {
+    CLIPBOARD_COMMANDS
+        .iter()
+        .map(|(program, _)| *program)
+        .collect()
+}
+
+/// Copy the text to the system clipboard using the first available clipboard command.
+/// Returns `false` if no clipboard command succeeded (e.g. in headless environments).
+///
+/// This deliberately shells out instead of using a clipboard crate. These link against the
+/// windowing systems (X11, Wayland, AppKit) which would add system build dependencies for
+/// a minor convenience. They also lose the clipboard content on X11 when Rustlings exits.
+pub fn copy_to_clipboard(text: &str) -> bool {
+    CLIPBOARD_COMMANDS.iter().any(|(program, args)| {
+        let Ok(mut handle) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return false;
+        };
+
+        // Dropping stdin closes the pipe to signal the end of the input.
+        let written = handle
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+
+        handle.wait().is_ok_and(|status| status.success()) && written
+    })
+}
+
+/// Show a desktop notification using the command of the platform.
+/// Returns `false` if no notification could be shown (e.g. on platforms without support).
+///
+/// Like `copy_to_clipboard`, this shells out instead of using a notification crate to avoid
+/// platform build dependencies. Linux uses `notify-send` (from `libnotify`) and macOS uses
+/// `osascript`. Windows has no such command, so notifications aren't supported there.
+pub fn send_desktop_notification(title: &str, body: &str) -> bool {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("osascript");
+        // Debug formatting escapes quotes for AppleScript strings.
+        cmd.arg("-e").arg(format!(
+            "display notification {body:?} with title {title:?}"
+        ));
+        cmd
+    } else if cfg!(windows) {
+        return false;
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.arg(title).arg(body);
+        cmd
+    };
+
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
 }
 
 // Parses parts of the output of `cargo metadata`.
@@ -52,8 +376,50 @@ struct CargoMetadata {
     target_directory: PathBuf,
 }
 
+/// Runs the exercises of the app state. Implemented by `CmdRunner` and by test doubles that
+/// return canned results without compiling anything.
+pub trait ExerciseRunner: Sync {
+    /// Check the exercise and return whether it passes.
+    /// Its output is appended to `output` if it is set.
+    fn run_exercise(&self, exercise: &Exercise, output: Option<&mut Vec<u8>>) -> Result<bool>;
+    /// Like `run_exercise`, but stdout and stderr of the commands are appended to separate buffers.
+    fn run_exercise_separated(
+        &self,
+        exercise: &Exercise,
+        stdout: &mut Vec<u8>,
+        stderr: &mut Vec<u8>,
+    ) -> Result<bool>;
+    /// Return whether the exercise compiles without running it.
+    fn build_exercise(&self, exercise: &Exercise) -> Result<bool>;
+    /// See `CmdRunner::set_live_output`.
+    fn set_live_output(&self, live_output: bool);
+    /// The maximum number of exercises to check in parallel if it was set.
+    fn jobs(&self) -> Option<NonZeroUsize>;
+    /// Whether the exercises are built with optimizations.
+    fn release(&self) -> bool;
+}
+
 pub struct CmdRunner {
     target_dir: PathBuf,
+    // Build the exercises of another Cargo project instead of the current one.
+    manifest_path: Option<PathBuf>,
+    // Appended to the environment variable `RUSTFLAGS` of Cargo commands.
+    extra_rustflags: Option<String>,
+    // Stream the captured output of commands to stdout while they run.
+    live_output: AtomicBool,
+    // Capture stderr into `stderr_output` instead of merging it into the output.
+    separate_stderr: AtomicBool,
+    stderr_output: Mutex<Vec<u8>>,
+    // Time limit for running exercise binaries and their tests.
+    timeout: Option<Duration>,
+    // Limit of the captured output of each command.
+    max_output_len: Option<usize>,
+    // Whether Cargo colors the captured output.
+    color: bool,
+    // Maximum number of exercises to check in parallel.
+    jobs: Option<NonZeroUsize>,
+    // Build and run the exercises with the release profile.
+    release: bool,
 }
 
 impl CmdRunner {
@@ -81,11 +447,109 @@ impl CmdRunner {
 
         Ok(Self {
             target_dir: metadata.target_directory,
+            manifest_path: None,
+            extra_rustflags: None,
+            live_output: AtomicBool::new(false),
+            separate_stderr: AtomicBool::new(false),
+            stderr_output: Mutex::new(Vec::new()),
+            timeout: Some(DEFAULT_TIMEOUT),
+            max_output_len: Some(DEFAULT_MAX_OUTPUT_LEN),
+            color: true,
+            jobs: None,
+            release: false,
         })
     }
 
+    /// Build the exercises with the given `Cargo.toml` file and target directory
+    /// (e.g. of a temporary copy of an exercise).
+    pub fn with_manifest(mut self, manifest_path: PathBuf, target_dir: PathBuf) -> Self {
+        self.manifest_path = Some(manifest_path);
+        self.target_dir = target_dir;
+        self
+    }
+
+    /// Pass extra flags to `rustc` when compiling exercises.
+    pub fn with_extra_rustflags(mut self, extra_rustflags: Option<String>) -> Self {
+        self.extra_rustflags = extra_rustflags.filter(|flags| !flags.trim().is_empty());
+        self
+    }
+
+    /// Let Cargo color the captured output.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Limit the number of exercises checked in parallel.
+    /// `None` uses the available parallelism.
+    pub fn with_jobs(mut self, jobs: Option<NonZeroUsize>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    #[inline]
+    pub fn jobs(&self) -> Option<NonZeroUsize> {
+        self.jobs
+    }
+
+    /// Build and run the exercises with the release profile instead of the dev profile.
+    /// Clippy is unaffected since lints don't depend on the profile.
+    pub fn with_release(mut self, release: bool) -> Self {
+        self.release = release;
+        self
+    }
+
+    #[inline]
+    pub fn release(&self) -> bool {
+        self.release
+    }
+
+    // The name of the profile directory of the built binaries in the target directory.
+    fn profile_dir(&self) -> &'static str {
+        if self.release {
+            "release"
+        } else {
+            "debug"
+        }
+    }
+
+    /// Limit the time of running exercise binaries and their tests. `None` disables the limit.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Truncate the captured output of each command to this number of bytes.
+    /// `None` disables the limit.
+    pub fn with_max_output_len(mut self, max_output_len: Option<usize>) -> Self {
+        self.max_output_len = max_output_len;
+        self
+    }
+
+    /// Also write the captured output to stdout while the commands run.
+    /// Only meant for running a single exercise, not for checking multiple ones in parallel.
+    #[inline]
+    pub fn set_live_output(&self, live_output: bool) {
+        self.live_output.store(live_output, Relaxed);
+    }
+
+    // The limits of commands which run code of the exercise.
+    fn limits(&self) -> CmdLimits {
+        CmdLimits {
+            max_output_len: self.max_output_len,
+            timeout: self.timeout,
+        }
+    }
+
+    // The buffer for the separately captured stderr if it is enabled.
+    fn stderr_output(&self) -> Option<&Mutex<Vec<u8>>> {
+        self.separate_stderr
+            .load(Relaxed)
+            .then_some(&self.stderr_output)
+    }
+
     pub fn cargo<'out>(
-        &self,
+        &'out self,
         subcommand: &str,
         bin_name: &str,
         output: Option<&'out mut Vec<u8>>,
@@ -93,36 +557,195 @@ impl CmdRunner {
         let mut cmd = Command::new("cargo");
         cmd.arg(subcommand).arg("-q").arg("--bin").arg(bin_name);
 
-        // A hack to make `cargo run` work when developing Rustlings.
-        #[cfg(debug_assertions)]
-        cmd.arg("--manifest-path")
-            .arg("dev/Cargo.toml")
-            .arg("--target-dir")
-            .arg(&self.target_dir);
+        // Clippy is run with `--profile test` which conflicts with `--release`.
+        if self.release && subcommand != "clippy" {
+            cmd.arg("--release");
+        }
+
+        if let Some(manifest_path) = &self.manifest_path {
+            cmd.arg("--manifest-path")
+                .arg(manifest_path)
+                .arg("--target-dir")
+                .arg(&self.target_dir);
+        } else if cfg!(debug_assertions) {
+            // A hack to make `cargo run` work when developing Rustlings.
+            cmd.arg("--manifest-path")
+                .arg("dev/Cargo.toml")
+                .arg("--target-dir")
+                .arg(&self.target_dir);
+        }
 
         if output.is_some() {
-            cmd.arg("--color").arg("always");
+            cmd.arg("--color")
+                .arg(if self.color { "always" } else { "never" });
+        }
+
+        if let Some(extra_rustflags) = &self.extra_rustflags {
+            let mut rustflags = env::var("RUSTFLAGS").unwrap_or_default();
+            if !rustflags.is_empty() {
+                rustflags.push(' ');
+            }
+            rustflags.push_str(extra_rustflags);
+            cmd.env("RUSTFLAGS", rustflags);
+        }
+
+        CargoSubcommand {
+            cmd,
+            output,
+            stderr_output: self.stderr_output(),
+            live: self.live_output.load(Relaxed),
+            limits: CmdLimits {
+                max_output_len: self.max_output_len,
+                // Only `cargo test` runs code of the exercise.
+                // `cargo_test` builds the tests without the limit.
+                timeout: if subcommand == "test" {
+                    self.timeout
+                } else {
+                    None
+                },
+            },
+        }
+    }
+
+    /// Build the tests of the binary and run them with `test_args`.
+    /// The time limit only applies to running the tests since building them can take long
+    /// (e.g. a cold build) without any infinite loop.
+    /// The boolean in the returned `Result` is true if the tests were built and passed.
+    pub fn cargo_test(
+        &self,
+        bin_name: &str,
+        mut output: Option<&mut Vec<u8>>,
+        test_args: &[&str],
+    ) -> Result<bool> {
+        let output_start = output.as_deref().map_or(0, Vec::len);
+        let stderr_start = self.stderr_output().map_or(0, |buffer| lock(buffer).len());
+
+        let mut build_cmd = self.cargo("test", bin_name, output.as_deref_mut());
+        build_cmd.args(["--no-run"]);
+        build_cmd.limits.timeout = None;
+        if !build_cmd.run("cargo test --no-run …")? {
+            return Ok(false);
+        }
+
+        // Discard the compiler output because it will be shown again by `cargo test`.
+        if let Some(output) = output.as_deref_mut() {
+            output.truncate(output_start);
+        }
+        if let Some(buffer) = self.stderr_output() {
+            lock(buffer).truncate(stderr_start);
         }
 
-        CargoSubcommand { cmd, output }
+        let mut test_cmd = self.cargo("test", bin_name, output);
+        if !test_args.is_empty() {
+            test_cmd.args(["--"]).args(test_args.iter().copied());
+        }
+        test_cmd.run("cargo test …")
     }
 
+    /// Run a command line in the shell (`sh` or `cmd` on Windows).
     /// The boolean in the returned `Result` is true if the command's exit status is success.
-    pub fn run_debug_bin(&self, bin_name: &str, output: Option<&mut Vec<u8>>) -> Result<bool> {
-        // 7 = "/debug/".len()
-        let mut bin_path =
-            PathBuf::with_capacity(self.target_dir.as_os_str().len() + 7 + bin_name.len());
+    pub fn run_shell(&self, command_line: &str, output: Option<&mut Vec<u8>>) -> Result<bool> {
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C");
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c");
+            cmd
+        };
+        cmd.arg(command_line);
+
+        let mut stderr_output = self.stderr_output().map(lock);
+        run_cmd(
+            &mut cmd,
+            command_line,
+            None,
+            output,
+            stderr_output.as_deref_mut(),
+            self.live_output.load(Relaxed),
+            self.limits(),
+        )
+    }
+
+    /// The boolean in the returned `Result` is true if the command's exit status is success.
+    /// The binary is taken from the directory of the profile that it was built with.
+    pub fn run_built_bin(
+        &self,
+        bin_name: &str,
+        stdin: Option<&str>,
+        output: Option<&mut Vec<u8>>,
+    ) -> Result<bool> {
+        let profile_dir = self.profile_dir();
+        // 2 separators
+        let mut bin_path = PathBuf::with_capacity(
+            self.target_dir.as_os_str().len() + profile_dir.len() + 2 + bin_name.len(),
+        );
         bin_path.push(&self.target_dir);
-        bin_path.push("debug");
+        bin_path.push(profile_dir);
         bin_path.push(bin_name);
 
-        run_cmd(Command::new(&bin_path), &bin_path.to_string_lossy(), output)
+        let mut stderr_output = self.stderr_output().map(lock);
+        run_cmd(
+            &mut Command::new(&bin_path),
+            &bin_path.to_string_lossy(),
+            stdin,
+            output,
+            stderr_output.as_deref_mut(),
+            self.live_output.load(Relaxed),
+            self.limits(),
+        )
     }
 }
 
+impl ExerciseRunner for CmdRunner {
+    fn run_exercise(&self, exercise: &Exercise, output: Option<&mut Vec<u8>>) -> Result<bool> {
+        RunnableExercise::run_exercise(exercise, output, self)
+    }
+
+    fn run_exercise_separated(
+        &self,
+        exercise: &Exercise,
+        stdout: &mut Vec<u8>,
+        stderr: &mut Vec<u8>,
+    ) -> Result<bool> {
+        lock(&self.stderr_output).clear();
+        self.separate_stderr.store(true, Relaxed);
+        let success = RunnableExercise::run_exercise(exercise, Some(stdout), self);
+        self.separate_stderr.store(false, Relaxed);
+        stderr.append(&mut lock(&self.stderr_output));
+        success
+    }
+
+    fn build_exercise(&self, exercise: &Exercise) -> Result<bool> {
+        self.cargo("build", exercise.name, None)
+            .run("cargo build …")
+    }
+
+    fn set_live_output(&self, live_output: bool) {
+        Self::set_live_output(self, live_output);
+    }
+
+    fn jobs(&self) -> Option<NonZeroUsize> {
+        Self::jobs(self)
+    }
+
+    fn release(&self) -> bool {
+        Self::release(self)
+    }
+}
+
+// A poisoned buffer only means that a command failed while its output was appended.
+fn lock(buffer: &Mutex<Vec<u8>>) -> std::sync::MutexGuard<'_, Vec<u8>> {
+    buffer.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 pub struct CargoSubcommand<'out> {
     cmd: Command,
     output: Option<&'out mut Vec<u8>>,
+    stderr_output: Option<&'out Mutex<Vec<u8>>>,
+    live: bool,
+    limits: CmdLimits,
 }
 
 impl CargoSubcommand<'_> {
@@ -136,12 +759,50 @@ impl CargoSubcommand<'_> {
     }
 
     /// The boolean in the returned `Result` is true if the command's exit status is success.
-    #[inline]
-    pub fn run(self, description: &str) -> Result<bool> {
-        run_cmd(self.cmd, description, self.output)
+    /// Failures which are known to be transient are retried up to `MAX_RETRIES` times.
+    /// The retries are logged into the output.
+    pub fn run(mut self, description: &str) -> Result<bool> {
+        let mut n_retries = 0;
+        let mut stderr_output = self.stderr_output.map(lock);
+
+        loop {
+            let output_start = self.output.as_deref().map_or(0, Vec::len);
+            let stderr_start = stderr_output.as_deref().map_or(0, Vec::len);
+            let success = run_cmd(
+                &mut self.cmd,
+                description,
+                None,
+                self.output.as_deref_mut(),
+                stderr_output.as_deref_mut(),
+                self.live,
+                self.limits,
+            )?;
+
+            // Without the output, transient failures can't be detected.
+            let Some(output) = self.output.as_deref_mut() else {
+                return Ok(success);
+            };
+
+            // Cargo reports its problems on stderr.
+            let failure_output = match stderr_output.as_deref() {
+                Some(stderr_output) => &stderr_output[stderr_start..],
+                None => &output[output_start..],
+            };
+            if success || n_retries == MAX_RETRIES || !is_transient_failure(failure_output) {
+                return Ok(success);
+            }
+
+            n_retries += 1;
+            writeln!(
+                output,
+                "The command `{description}` failed because of a temporary Cargo problem. Retrying ({n_retries}/{MAX_RETRIES})…\n",
+            )?;
+        }
     }
 }
 
+const MAX_RETRIES: u8 = 2;
+
 const CARGO_METADATA_ERR: &str = "Failed to run the command `cargo metadata …`
 Did you already install Rust?
 Try running `cargo --version` to diagnose the problem.";
@@ -156,8 +817,186 @@ mod tests {
         cmd.arg("Hello");
 
         let mut output = Vec::with_capacity(8);
-        run_cmd(cmd, "echo …", Some(&mut output)).unwrap();
+        run_cmd(
+            &mut cmd,
+            "echo …",
+            None,
+            Some(&mut output),
+            None,
+            false,
+            CmdLimits {
+                max_output_len: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
 
         assert_eq!(output, b"Hello\n\n");
     }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_cargo_test_slow_build() {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        let dir = env::temp_dir().join(format!("rustlings-slow-build-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"slow\"\nedition = \"2021\"\n[[bin]]\nname = \"slow\"\npath = \"slow.rs\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("slow.rs"), "fn main() {}\n#[test]\nfn test() {}\n").unwrap();
+        // Linking takes longer than the time limit.
+        let linker = dir.join("slow-linker");
+        fs::write(&linker, "#!/bin/sh\nsleep 2\nexec cc \"$@\"\n").unwrap();
+        fs::set_permissions(&linker, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let cmd_runner = CmdRunner::build()
+            .unwrap()
+            .with_manifest(dir.join("Cargo.toml"), dir.join("target"))
+            .with_extra_rustflags(Some(format!("-C linker={}", linker.display())))
+            .with_timeout(Some(Duration::from_secs(1)));
+
+        let mut output = Vec::new();
+        let success = cmd_runner
+            .cargo_test("slow", Some(&mut output), &[])
+            .unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let output = String::from_utf8_lossy(&output);
+        assert!(success, "{output}");
+        assert!(!output.contains("Is there an infinite loop?"));
+    }
+
+    #[test]
+    fn test_run_cmd_separate_stderr() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo out; echo err >&2");
+
+        let mut output = Vec::new();
+        let mut stderr_output = Vec::new();
+        run_cmd(
+            &mut cmd,
+            "sh …",
+            None,
+            Some(&mut output),
+            Some(&mut stderr_output),
+            false,
+            CmdLimits {
+                max_output_len: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(output, b"out\n\n");
+        assert_eq!(stderr_output, b"err\n");
+    }
+
+    #[test]
+    fn test_run_cmd_max_output_len() {
+        let mut cmd = Command::new("echo");
+        cmd.arg("Hello");
+
+        let mut output = Vec::new();
+        run_cmd(
+            &mut cmd,
+            "echo …",
+            None,
+            Some(&mut output),
+            None,
+            false,
+            CmdLimits {
+                max_output_len: Some(2),
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            output,
+            "He\n… output truncated (4 bytes omitted)\n".as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_run_cmd_live() {
+        let mut cmd = Command::new("echo");
+        cmd.arg("Hello");
+
+        let mut output = Vec::with_capacity(8);
+        run_cmd(
+            &mut cmd,
+            "echo …",
+            None,
+            Some(&mut output),
+            None,
+            true,
+            CmdLimits {
+                max_output_len: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(output, b"Hello\n\n");
+    }
+
+    #[test]
+    fn test_run_cmd_stdin() {
+        let mut output = Vec::with_capacity(8);
+        run_cmd(
+            &mut Command::new("cat"),
+            "cat",
+            Some("Hello\n"),
+            Some(&mut output),
+            None,
+            false,
+            CmdLimits {
+                max_output_len: None,
+                timeout: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(output, b"Hello\n\n");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_run_cmd_timeout() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("10");
+
+        let mut output = Vec::new();
+        let success = run_cmd(
+            &mut cmd,
+            "sleep",
+            None,
+            Some(&mut output),
+            None,
+            false,
+            CmdLimits {
+                max_output_len: None,
+                timeout: Some(Duration::from_millis(100)),
+            },
+        )
+        .unwrap();
+
+        assert!(!success);
+        assert!(output.ends_with(b"Is there an infinite loop?\n"));
+    }
+
+    #[test]
+    fn test_is_transient_failure() {
+        assert!(is_transient_failure(
+            b"Blocking waiting for file lock on build directory\nerror: failed to open"
+        ));
+        // Waiting for the lock doesn't matter if the code doesn't compile.
+        assert!(!is_transient_failure(
+            b"Blocking waiting for file lock on build directory\nerror[E0308]: mismatched types"
+        ));
+        assert!(!is_transient_failure(b"error: could not compile `intro1`"));
+    }
 }
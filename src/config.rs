@@ -0,0 +1,117 @@
+use anyhow::{Context, Error, Result};
+use serde::Deserialize;
+use std::{fs, io::ErrorKind};
+
+use crate::theme::{CompletionPreset, ThemeBase};
+
+/// The optional user config file in the directory of the exercises.
+pub const CONFIG_FILE_NAME: &str = ".rustlings-config.toml";
+
+/// Colors of the theme. Missing colors are taken from the base theme.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub base: Option<ThemeBase>,
+    pub done: Option<String>,
+    pub hint: Option<String>,
+    pub warning: Option<String>,
+    pub progress_done: Option<String>,
+    pub progress_pending: Option<String>,
+    pub diff_added: Option<String>,
+    pub diff_removed: Option<String>,
+}
+
+/// Texts shown when an exercise or all exercises are done. Missing texts are taken from the preset.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompletionConfig {
+    pub preset: Option<CompletionPreset>,
+    /// Shown next to "Exercise done" and as the done state in the status line.
+    pub glyph: Option<String>,
+    /// Shown after the key of the `next` command instead of "to move on to the next exercise".
+    pub move_on: Option<String>,
+    /// Shown above the final message of the exercises when all exercises are done.
+    pub final_message: Option<String>,
+}
+
+/// Keys of the commands in the watch mode. Missing keys use the defaults.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub next: Option<char>,
+    pub prev: Option<char>,
+    pub back: Option<char>,
+    pub skip: Option<char>,
+    pub run: Option<char>,
+    pub hint: Option<char>,
+    pub toggle_hints: Option<char>,
+    pub hint_pager: Option<char>,
+    pub bookmark: Option<char>,
+    pub copy_solution: Option<char>,
+    pub peek: Option<char>,
+    pub diff: Option<char>,
+    pub note: Option<char>,
+    pub report: Option<char>,
+    pub groups: Option<char>,
+    pub stats: Option<char>,
+    pub wrap: Option<char>,
+    pub pause_watcher: Option<char>,
+    pub redraw: Option<char>,
+    pub view: Option<char>,
+    pub edit: Option<char>,
+    pub list: Option<char>,
+    pub check: Option<char>,
+    pub check_all: Option<char>,
+    pub next_failure: Option<char>,
+    pub reset: Option<char>,
+    pub undo: Option<char>,
+    pub reload: Option<char>,
+    pub quit: Option<char>,
+}
+
+/// Deserialized from the config file.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: ThemeConfig,
+    pub keys: KeysConfig,
+    pub completion: CompletionConfig,
+    /// Extra flags passed to `rustc` when compiling exercises.
+    pub rustflags: Option<String>,
+    /// The default of the manual run mode if neither `--manual-run` nor `--no-manual-run` is passed.
+    pub manual_run: Option<bool>,
+    /// Time limit in seconds for running an exercise or its tests. `0` disables the limit.
+    pub timeout: Option<u64>,
+    /// Milliseconds to wait for more changes of an exercise file before running it again.
+    pub debounce_ms: Option<u64>,
+    /// Maximum number of captured output bytes of each command. `0` disables the limit.
+    pub max_output_len: Option<usize>,
+    /// The number of solution lines shown by the `peek` command. `0` disables the command.
+    pub peek_lines: Option<usize>,
+    /// Explain common compiler errors below the output of the watch mode. Enabled by default.
+    pub error_notes: Option<bool>,
+    /// Show the hints beside the output instead of below it on wide terminals.
+    pub two_columns: Option<bool>,
+    /// Also show the diagnostics of rust-analyzer if an exercise fails and rust-analyzer is
+    /// installed. Analyzing the exercises takes a few seconds.
+    pub rust_analyzer: Option<bool>,
+}
+
+impl Config {
+    /// Read the config file if it exists. Otherwise, return the default config.
+    pub fn read() -> Result<Self> {
+        match fs::read_to_string(CONFIG_FILE_NAME) {
+            Ok(file_content) => toml_edit::de::from_str(&file_content)
+                .with_context(|| format!("Failed to parse the config file `{CONFIG_FILE_NAME}`")),
+            Err(e) => {
+                if e.kind() == ErrorKind::NotFound {
+                    return Ok(Self::default());
+                }
+
+                Err(Error::from(e).context(format!(
+                    "Failed to read the config file `{CONFIG_FILE_NAME}`"
+                )))
+            }
+        }
+    }
+}
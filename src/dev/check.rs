@@ -10,7 +10,7 @@ use std::{
 };
 
 use crate::{
-    cargo_toml::{append_bins, bins_start_end_ind, BINS_BUFFER_CAPACITY},
+    cargo_toml::updated_cargo_toml,
     cmd::CmdRunner,
     exercise::{RunnableExercise, OUTPUT_CAPACITY},
     info_file::{ExerciseInfo, InfoFile},
@@ -34,13 +34,11 @@ fn check_cargo_toml(
     let current_cargo_toml = fs::read_to_string(cargo_toml_path)
         .with_context(|| format!("Failed to read the file `{cargo_toml_path}`"))?;
 
-    let (bins_start_ind, bins_end_ind) = bins_start_end_ind(&current_cargo_toml)?;
+    // Both the `bin` list and the dependencies of the exercises have to be up-to-date.
+    let new_cargo_toml =
+        updated_cargo_toml(exercise_infos, &current_cargo_toml, exercise_path_prefix)?;
 
-    let old_bins = &current_cargo_toml.as_bytes()[bins_start_ind..bins_end_ind];
-    let mut new_bins = Vec::with_capacity(BINS_BUFFER_CAPACITY);
-    append_bins(&mut new_bins, exercise_infos, exercise_path_prefix);
-
-    if old_bins != new_bins {
+    if current_cargo_toml.as_bytes() != new_cargo_toml {
         if cfg!(debug_assertions) {
             bail!("The file `dev/Cargo.toml` is outdated. Run `cargo run -- dev update` to update it. Then run `cargo run -- dev check` again");
         }
@@ -78,10 +76,32 @@ fn check_info_file_exercises(info_file: &InfoFile) -> Result<HashSet<PathBuf>> {
             }
         }
 
-        if exercise_info.hint.trim_ascii().is_empty() {
+        if let Some(hint_file) = &exercise_info.hint_file {
+            let hint = fs::read_to_string(hint_file).with_context(|| {
+                format!("Failed to read the hint file `{hint_file}` of the exercise `{name}`")
+            })?;
+            if hint.trim_ascii().is_empty() {
+                bail!("The hint file `{hint_file}` of the exercise `{name}` is empty");
+            }
+            if exercise_info.hint.iter().next().is_some() {
+                bail!("The exercise `{name}` has a hint and a hint file. Remove one of them since only the hint file is used");
+            }
+        } else if exercise_info.hint.iter().next().is_none() {
+            bail!("The exercise `{name}` has an empty list of hints in `info.toml`");
+        } else if exercise_info
+            .hint
+            .iter()
+            .any(|hint| hint.trim_ascii().is_empty())
+        {
             bail!("The exercise `{name}` has an empty hint. Please provide a hint or at least tell the user why a hint isn't needed for this exercise");
         }
 
+        for prerequisite in &exercise_info.prerequisites {
+            if !names.contains(prerequisite.as_str()) {
+                bail!("The prerequisite `{prerequisite}` of the exercise `{name}` has to be an exercise before it in `info.toml`");
+            }
+        }
+
         if !names.insert(name) {
             bail!("The exercise name `{name}` is duplicated. Exercise names must all be unique");
         }
@@ -198,9 +218,8 @@ fn check_exercises_unsolved(
     let n_handles = handles.len();
     write!(stdout, "Progress: 0/{n_handles}")?;
     stdout.flush()?;
-    let mut handle_num = 1;
 
-    for (exercise_name, handle) in handles {
+    for (handle_num, (exercise_name, handle)) in (1..).zip(handles) {
         let Ok(result) = handle.join() else {
             bail!("Panic while trying to run the exercise {exercise_name}");
         };
@@ -215,7 +234,6 @@ fn check_exercises_unsolved(
 
         write!(stdout, "\rProgress: {handle_num}/{n_handles}")?;
         stdout.flush()?;
-        handle_num += 1;
     }
     stdout.write_all(b"\n")?;
 
@@ -295,9 +313,9 @@ fn check_solutions(
     let n_handles = handles.len();
     write!(stdout, "Progress: 0/{n_handles}")?;
     stdout.flush()?;
-    let mut handle_num = 1;
 
-    for (exercise_info, handle) in info_file.exercises.iter().zip(handles) {
+    for (handle_num, (exercise_info, handle)) in (1..).zip(info_file.exercises.iter().zip(handles))
+    {
         let Ok(check_result) = handle.join() else {
             bail!(
                 "Panic while trying to run the solution of the exercise {}",
@@ -324,7 +342,6 @@ fn check_solutions(
 
         write!(stdout, "\rProgress: {handle_num}/{n_handles}")?;
         stdout.flush()?;
-        handle_num += 1;
     }
     stdout.write_all(b"\n")?;
 
@@ -120,7 +120,41 @@ name = "???"
 # the exercise as done when there are no warnings left.
 # strict_clippy = false
 
+# You can optionally set `deny_warnings` to `true` (the default is `false`) to fail the
+# exercise if compiling it results in warnings.
+# deny_warnings = false
+
+# Optional crates needed by the exercise in the form of Cargo. They are added to the
+# `[dependencies]` table of `Cargo.toml` by `rustlings dev update`.
+# [exercises.dependencies]
+# rand = "0.8"
+# serde = { version = "1", features = ["derive"] }
+
+# Optional input to be written to the stdin of the exercise when running it.
+# stdin = """???"""
+
+# Optional shell commands to be run before and after checking the exercise.
+# A failing `pre_check` command fails the check without compiling the exercise.
+# pre_check = "???"
+# post_check = "???"
+
+# Optional output that the exercise has to print to be considered done.
+# Trailing whitespace is ignored.
+# expected_output = """???"""
+
+# Optional difficulty: "easy", "medium" or "hard".
+# difficulty = "easy"
+
+# Optional challenges don't count towards the progress required to finish the exercises.
+# optional = true
+
 # A multi-line hint to be shown to users on request.
+# You can also provide a list of hints which are revealed one at a time:
+# hint = ["First hint", "Second hint"]
+# Or put a long hint into a Markdown file and replace `hint` with its path:
+# hint_file = "hints/???.md"
+# Exercises that have to be done first if started with `--enforce-prerequisites`:
+# prerequisites = ["???"]
 hint = """???"""
 "#;
 
@@ -0,0 +1,68 @@
+/// A line of a line-based diff.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Unchanged(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compute a line diff from `old` to `new` based on their longest common subsequence of lines.
+/// Quadratic in the number of lines which is fine for exercise files.
+pub fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // `lcs_lens[i][j]` is the length of the longest common subsequence of `old[i..]` and `new[j..]`.
+    let mut lcs_lens = vec![vec![0_u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs_lens[i][j] = if old[i] == new[j] {
+                lcs_lens[i + 1][j + 1] + 1
+            } else {
+                lcs_lens[i + 1][j].max(lcs_lens[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Unchanged(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs_lens[i + 1][j] >= lcs_lens[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff() {
+        assert_eq!(
+            line_diff("a\nb\nc\nd\n", "a\nx\nc\nd\ne\n"),
+            [
+                DiffLine::Unchanged("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Unchanged("c"),
+                DiffLine::Unchanged("d"),
+                DiffLine::Added("e"),
+            ],
+        );
+        assert_eq!(line_diff("", "a"), [DiffLine::Added("a")]);
+        assert_eq!(line_diff("a", ""), [DiffLine::Removed("a")]);
+    }
+}
@@ -0,0 +1,121 @@
+// Beginner-friendly notes on common compiler errors. Sorted by the error code.
+const NOTES: &[(&str, &str)] = &[
+    (
+        "E0004",
+        "A `match` has to handle every possible value. Add the missing patterns or a `_` arm.",
+    ),
+    (
+        "E0061",
+        "The function is called with a different number of arguments than it has parameters.",
+    ),
+    (
+        "E0106",
+        "A returned or stored reference needs a lifetime to tell how long it stays valid.",
+    ),
+    (
+        "E0277",
+        "A type doesn't implement a trait that is required here. Maybe derive or implement it.",
+    ),
+    (
+        "E0308",
+        "The type of a value doesn't match the expected type. Check the types of both sides.",
+    ),
+    (
+        "E0369",
+        "The operator isn't implemented for these types (e.g. comparing values without `PartialEq`).",
+    ),
+    (
+        "E0382",
+        "A value was moved to another owner and can't be used anymore. Borrow it with `&` or clone it.",
+    ),
+    (
+        "E0384",
+        "Variables are immutable by default. Declare it with `let mut` to assign to it again.",
+    ),
+    (
+        "E0425",
+        "The name isn't defined in this scope. Check for typos or a missing `use`.",
+    ),
+    (
+        "E0433",
+        "The path can't be resolved. Maybe a `use` is missing or a module name is misspelled.",
+    ),
+    (
+        "E0499",
+        "There can only be one mutable reference to a value at a time.",
+    ),
+    (
+        "E0502",
+        "A value can't be borrowed mutably while it is also borrowed immutably.",
+    ),
+    (
+        "E0505",
+        "A value can't be moved while it is still borrowed.",
+    ),
+    (
+        "E0507",
+        "A value can't be moved out of a reference. Borrow it, clone it or use `std::mem::take`.",
+    ),
+    (
+        "E0596",
+        "Mutating through a binding or reference requires `mut` like `let mut x` or `&mut x`.",
+    ),
+    (
+        "E0597",
+        "A reference outlives the value it points to. The value is dropped at the end of its scope.",
+    ),
+    (
+        "E0599",
+        "The method doesn't exist for this type. Maybe a trait has to be imported or implemented.",
+    ),
+    (
+        "E0603",
+        "The item is private. Make it public with `pub` to use it outside of its module.",
+    ),
+];
+
+/// The notes on the errors in the compiler output in the order of their first occurrence.
+/// The codes are found in colored output too since `error[E…]` isn't interrupted by styles.
+pub fn error_notes(output: &[u8]) -> Vec<(&'static str, &'static str)> {
+    let mut notes = Vec::new();
+
+    // `[E` + 4 digits + `]`
+    for window in output.windows(7) {
+        let [b'[', b'E', code @ .., b']'] = window else {
+            continue;
+        };
+        if !code.iter().all(u8::is_ascii_digit) {
+            continue;
+        }
+
+        let Ok(ind) = NOTES.binary_search_by(|(note_code, _)| note_code.as_bytes()[1..].cmp(code))
+        else {
+            continue;
+        };
+        if !notes.contains(&NOTES[ind]) {
+            notes.push(NOTES[ind]);
+        }
+    }
+
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes() {
+        // Required by the binary search.
+        assert!(NOTES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        let output = b"error[E0502]: cannot borrow\nerror[E0382]: borrow of moved value\nerror[E0502]: cannot borrow\nerror[E9999]: unknown";
+        let codes = error_notes(output)
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect::<Vec<_>>();
+        assert_eq!(codes, ["E0502", "E0382"]);
+
+        assert!(error_notes(b"warning: unused variable").is_empty());
+    }
+}
@@ -1,38 +1,97 @@
 use anyhow::Result;
-use crossterm::{
-    style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor},
-    QueueableCommand,
+use crossterm::style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor};
+use std::{
+    io::{self, Write},
+    time::Duration,
 };
-use std::io::{self, StdoutLock, Write};
 
 use crate::{
     cmd::CmdRunner,
+    diff::{line_diff, DiffLine},
+    info_file::Difficulty,
     term::{self, terminal_file_link, write_ansi, CountedWrite},
+    theme::Theme,
 };
 
 /// The initial capacity of the output buffer.
 pub const OUTPUT_CAPACITY: usize = 1 << 14;
 
-pub fn solution_link_line(stdout: &mut StdoutLock, solution_path: &str) -> io::Result<()> {
-    stdout.queue(SetAttribute(Attribute::Bold))?;
+pub fn solution_link_line(
+    stdout: &mut (impl Write + CountedWrite),
+    solution_path: &str,
+    theme: &Theme,
+) -> io::Result<()> {
+    theme.queue_style(stdout, SetAttribute(Attribute::Bold))?;
     stdout.write_all(b"Solution")?;
-    stdout.queue(ResetColor)?;
+    theme.queue_style(stdout, ResetColor)?;
     stdout.write_all(b" for comparison: ")?;
     if let Some(canonical_path) = term::canonicalize(solution_path) {
-        terminal_file_link(stdout, solution_path, &canonical_path, Color::Cyan)?;
+        terminal_file_link(stdout, solution_path, &canonical_path, Color::Cyan, theme)?;
     } else {
         stdout.write_all(solution_path.as_bytes())?;
     }
     stdout.write_all(b"\n")
 }
 
+// Compare the output of an exercise binary with the expected output ignoring trailing whitespace.
+// If they don't match, a diff is appended to the `output` buffer.
+fn matches_expected_output(bin_output: &[u8], expected_output: &str, output: &mut Vec<u8>) -> bool {
+    let bin_output = String::from_utf8_lossy(bin_output);
+    let (bin_output, expected_output) = (bin_output.trim_end(), expected_output.trim_end());
+    if bin_output == expected_output {
+        return true;
+    }
+
+    write_ansi(output, SetAttribute(Attribute::Bold));
+    write_ansi(output, SetForegroundColor(Color::Red));
+    output.extend_from_slice(b"The output of the exercise doesn't match the expected output");
+    write_ansi(output, ResetColor);
+    output.extend_from_slice(b" (- your output, + the expected output)\n");
+
+    for line in line_diff(bin_output, expected_output) {
+        match line {
+            DiffLine::Unchanged(line) => {
+                output.extend_from_slice(b"  ");
+                output.extend_from_slice(line.as_bytes());
+            }
+            DiffLine::Removed(line) => {
+                write_ansi(output, SetForegroundColor(Color::Red));
+                output.extend_from_slice(b"- ");
+                output.extend_from_slice(line.as_bytes());
+                write_ansi(output, ResetColor);
+            }
+            DiffLine::Added(line) => {
+                write_ansi(output, SetForegroundColor(Color::Green));
+                output.extend_from_slice(b"+ ");
+                output.extend_from_slice(line.as_bytes());
+                write_ansi(output, ResetColor);
+            }
+        }
+        output.push(b'\n');
+    }
+
+    false
+}
+
 // Run an exercise binary and append its output to the `output` buffer.
+// If `expected_output` is set, the run is only successful if the output of the binary
+// (stdout and stderr merged) matches it.
 // Compilation must be done before calling this method.
 fn run_bin(
     bin_name: &str,
-    mut output: Option<&mut Vec<u8>>,
+    stdin: Option<&str>,
+    expected_output: Option<&str>,
+    output: Option<&mut Vec<u8>>,
     cmd_runner: &CmdRunner,
 ) -> Result<bool> {
+    // The output is required to compare it with the expected output.
+    let mut required_output = Vec::new();
+    let mut output = match output {
+        Some(output) => Some(output),
+        None if expected_output.is_some() => Some(&mut required_output),
+        None => None,
+    };
+
     if let Some(output) = output.as_deref_mut() {
         write_ansi(output, SetAttribute(Attribute::Underlined));
         output.extend_from_slice(b"Output");
@@ -40,10 +99,17 @@ fn run_bin(
         output.push(b'\n');
     }
 
-    let success = cmd_runner.run_debug_bin(bin_name, output.as_deref_mut())?;
+    let bin_output_start = output.as_deref().map_or(0, Vec::len);
+    let exit_success = cmd_runner.run_built_bin(bin_name, stdin, output.as_deref_mut())?;
+    let mut success = exit_success;
 
     if let Some(output) = output {
-        if !success {
+        if let (true, Some(expected_output)) = (exit_success, expected_output) {
+            let bin_output = output[bin_output_start..].to_vec();
+            success = matches_expected_output(&bin_output, expected_output, output);
+        }
+
+        if !exit_success {
             // This output is important to show the user that something went wrong.
             // Otherwise, calling something like `exit(1)` in an exercise without further output
             // leaves the user confused about why the exercise isn't done yet.
@@ -58,8 +124,106 @@ fn run_bin(
     Ok(success)
 }
 
+// Run a `pre_check` or `post_check` command of an exercise and append its output
+// between delimiters to the `output` buffer.
+fn run_hook(
+    name: &str,
+    command: &str,
+    mut output: Option<&mut Vec<u8>>,
+    cmd_runner: &CmdRunner,
+) -> Result<bool> {
+    if let Some(output) = output.as_deref_mut() {
+        write_ansi(output, SetAttribute(Attribute::Underlined));
+        write!(output, "{name}: {command}")?;
+        write_ansi(output, ResetColor);
+        output.push(b'\n');
+    }
+
+    let success = cmd_runner.run_shell(command, output.as_deref_mut())?;
+
+    if let Some(output) = output {
+        if !success {
+            write_ansi(output, SetAttribute(Attribute::Bold));
+            write_ansi(output, SetForegroundColor(Color::Red));
+            write!(output, "{name} failed (nonzero exit code)")?;
+            write_ansi(output, ResetColor);
+            output.push(b'\n');
+        }
+
+        write_ansi(output, SetAttribute(Attribute::Underlined));
+        write!(output, "End of {name}")?;
+        write_ansi(output, ResetColor);
+        output.extend_from_slice(b"\n\n");
+    }
+
+    Ok(success)
+}
+
+// Remove ANSI escape sequences like colors from a line.
+fn strip_ansi(line: &[u8], stripped: &mut Vec<u8>) {
+    stripped.clear();
+
+    let mut ind = 0;
+    while let Some(&byte) = line.get(ind) {
+        if byte == 0x1b {
+            ind += term::ansi_escape_len(&line[ind..]);
+        } else {
+            stripped.push(byte);
+            ind += 1;
+        }
+    }
+}
+
+/// Move the compiler warnings in the output of running an exercise to the returned buffer.
+/// A warning starts with a line beginning with `warning` and ends with an empty line.
+/// The output of the exercise binary itself is kept as it is.
+pub fn split_off_warnings(output: &mut Vec<u8>) -> Vec<u8> {
+    let mut warnings = Vec::new();
+    let mut rest = Vec::with_capacity(output.len());
+    let mut stripped = Vec::new();
+    let mut in_warning = false;
+    let mut in_bin_output = false;
+
+    for line in output.split_inclusive(|c| *c == b'\n') {
+        if in_bin_output {
+            rest.extend_from_slice(line);
+            continue;
+        }
+
+        strip_ansi(line, &mut stripped);
+        let stripped = stripped.trim_ascii_end();
+
+        // The header written by `run_bin`.
+        if stripped == b"Output" {
+            in_bin_output = true;
+            rest.extend_from_slice(line);
+            continue;
+        }
+
+        if stripped.starts_with(b"warning:") || stripped.starts_with(b"warning[") {
+            in_warning = true;
+        } else if stripped.starts_with(b"error") {
+            in_warning = false;
+        }
+
+        if in_warning {
+            warnings.extend_from_slice(line);
+            if stripped.is_empty() {
+                in_warning = false;
+            }
+        } else {
+            rest.extend_from_slice(line);
+        }
+    }
+
+    *output = rest;
+    warnings
+}
+
 /// See `info_file::ExerciseInfo`
 pub struct Exercise {
+    /// Index of the exercise in the info file (also of its embedded files).
+    pub info_ind: usize,
     pub dir: Option<&'static str>,
     pub name: &'static str,
     /// Path of the exercise file starting with the `exercises/` directory.
@@ -67,14 +231,48 @@ pub struct Exercise {
     pub canonical_path: Option<String>,
     pub test: bool,
     pub strict_clippy: bool,
-    pub hint: &'static str,
+    pub deny_warnings: bool,
+    pub stdin: Option<&'static str>,
+    pub pre_check: Option<&'static str>,
+    pub post_check: Option<&'static str>,
+    pub expected_output: Option<&'static str>,
+    pub difficulty: Option<Difficulty>,
+    /// A challenge that isn't required to finish the exercises.
+    pub optional: bool,
+    /// Names of the exercises that lock this one until they are done.
+    pub prerequisites: &'static [&'static str],
+    /// Usually only one hint. Multiple hints are revealed one at a time.
+    pub hints: &'static [&'static str],
     pub done: bool,
+    /// The hint was shown at least once (also in a previous session).
+    pub hint_viewed: bool,
+    /// How often a hint was shown in the watch mode over all sessions. Resetting doesn't
+    /// change it.
+    pub n_hints_shown: u32,
+    /// The first lines of the solution were shown in the watch mode.
+    pub solution_peeked: bool,
+    /// Time spent in the watch mode while the exercise was pending.
+    pub time_spent: Duration,
+    /// Marked by the user to revisit it later.
+    pub bookmarked: bool,
+    /// Personal notes of the user, one line each.
+    pub notes: Vec<String>,
+    /// Modification time of the exercise file (seconds since the Unix epoch) when the exercise
+    /// was marked as done. Used to detect changes while Rustlings wasn't running.
+    pub done_mtime: Option<u64>,
+    /// When the exercise was done for the first time (seconds since the Unix epoch).
+    /// Kept if the exercise becomes pending again.
+    pub first_done_at: Option<u64>,
 }
 
 impl Exercise {
-    pub fn terminal_file_link<'a>(&self, writer: &mut impl CountedWrite<'a>) -> io::Result<()> {
+    pub fn terminal_file_link(
+        &self,
+        writer: &mut impl CountedWrite,
+        theme: &Theme,
+    ) -> io::Result<()> {
         if let Some(canonical_path) = self.canonical_path.as_deref() {
-            return terminal_file_link(writer, self.path, canonical_path, Color::Blue);
+            return terminal_file_link(writer, self.path, canonical_path, Color::Blue, theme);
         }
 
         writer.write_str(self.path)
@@ -85,23 +283,74 @@ pub trait RunnableExercise {
     fn name(&self) -> &str;
     fn dir(&self) -> Option<&str>;
     fn strict_clippy(&self) -> bool;
+    fn deny_warnings(&self) -> bool;
     fn test(&self) -> bool;
+    fn stdin(&self) -> Option<&str>;
+    fn pre_check(&self) -> Option<&str>;
+    fn post_check(&self) -> Option<&str>;
+    fn expected_output(&self) -> Option<&str>;
 
-    // Compile, check and run the exercise or its solution (depending on `bin_name´).
+    // Run the exercise's checks between its `pre_check` and `post_check` commands.
     // The output is written to the `output` buffer after clearing it.
     fn run<const FORCE_STRICT_CLIPPY: bool>(
         &self,
         bin_name: &str,
         mut output: Option<&mut Vec<u8>>,
         cmd_runner: &CmdRunner,
+    ) -> Result<bool> {
+        // Separate because the checks clear the output.
+        let mut pre_check_output = output.is_some().then(Vec::new);
+        if let Some(pre_check) = self.pre_check() {
+            if !run_hook(
+                "Pre-check",
+                pre_check,
+                pre_check_output.as_mut(),
+                cmd_runner,
+            )? {
+                if let (Some(output), Some(pre_check_output)) = (output, pre_check_output) {
+                    *output = pre_check_output;
+                }
+
+                return Ok(false);
+            }
+        }
+
+        let success =
+            self.run_checks::<FORCE_STRICT_CLIPPY>(bin_name, output.as_deref_mut(), cmd_runner)?;
+
+        if let Some(post_check) = self.post_check() {
+            run_hook("Post-check", post_check, output.as_deref_mut(), cmd_runner)?;
+        }
+
+        if let (Some(output), Some(pre_check_output)) = (output, pre_check_output) {
+            output.splice(0..0, pre_check_output);
+        }
+
+        Ok(success)
+    }
+
+    // Compile, check and run the exercise or its solution (depending on `bin_name´).
+    // The output is written to the `output` buffer after clearing it.
+    fn run_checks<const FORCE_STRICT_CLIPPY: bool>(
+        &self,
+        bin_name: &str,
+        mut output: Option<&mut Vec<u8>>,
+        cmd_runner: &CmdRunner,
     ) -> Result<bool> {
         if let Some(output) = output.as_deref_mut() {
             output.clear();
         }
 
-        let build_success = cmd_runner
-            .cargo("build", bin_name, output.as_deref_mut())
-            .run("cargo build …")?;
+        // `cargo rustc` only passes the flags to the compilation of the exercise itself.
+        let build_success = if self.deny_warnings() {
+            let mut build_cmd = cmd_runner.cargo("rustc", bin_name, output.as_deref_mut());
+            build_cmd.args(["--", "-D", "warnings"]);
+            build_cmd.run("cargo rustc …")?
+        } else {
+            cmd_runner
+                .cargo("build", bin_name, output.as_deref_mut())
+                .run("cargo build …")?
+        };
         if !build_success {
             return Ok(false);
         }
@@ -112,14 +361,20 @@ pub trait RunnableExercise {
         }
 
         if self.test() {
-            let output_is_some = output.is_some();
-            let mut test_cmd = cmd_runner.cargo("test", bin_name, output.as_deref_mut());
-            if output_is_some {
-                test_cmd.args(["--", "--color", "always", "--format", "pretty"]);
-            }
-            let test_success = test_cmd.run("cargo test …")?;
+            let test_args: &[&str] = if output.is_some() {
+                &["--color", "always", "--format", "pretty"]
+            } else {
+                &[]
+            };
+            let test_success = cmd_runner.cargo_test(bin_name, output.as_deref_mut(), test_args)?;
             if !test_success {
-                run_bin(bin_name, output, cmd_runner)?;
+                run_bin(
+                    bin_name,
+                    self.stdin(),
+                    self.expected_output(),
+                    output,
+                    cmd_runner,
+                )?;
                 return Ok(false);
             }
 
@@ -139,7 +394,13 @@ pub trait RunnableExercise {
         }
 
         let clippy_success = clippy_cmd.run("cargo clippy …")?;
-        let run_success = run_bin(bin_name, output, cmd_runner)?;
+        let run_success = run_bin(
+            bin_name,
+            self.stdin(),
+            self.expected_output(),
+            output,
+            cmd_runner,
+        )?;
 
         Ok(clippy_success && run_success)
     }
@@ -204,8 +465,97 @@ impl RunnableExercise for Exercise {
         self.strict_clippy
     }
 
+    #[inline]
+    fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
     #[inline]
     fn test(&self) -> bool {
         self.test
     }
+
+    #[inline]
+    fn stdin(&self) -> Option<&str> {
+        self.stdin
+    }
+
+    #[inline]
+    fn pre_check(&self) -> Option<&str> {
+        self.pre_check
+    }
+
+    #[inline]
+    fn post_check(&self) -> Option<&str> {
+        self.post_check
+    }
+
+    #[inline]
+    fn expected_output(&self) -> Option<&str> {
+        self.expected_output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings() {
+        let mut output = b"\x1b[1m\x1b[33mwarning\x1b[0m: unused variable: `x`
+ --> exercises/x.rs:2:9
+
+\x1b[1;31merror\x1b[0m: mismatched types
+ --> exercises/x.rs:3:5
+
+warning: `exercises` (bin \"x\") generated 1 warning
+
+\x1b[4mOutput\x1b[0m
+warning: printed by the exercise
+
+"
+        .to_vec();
+
+        let warnings = split_off_warnings(&mut output);
+
+        assert_eq!(
+            warnings,
+            b"\x1b[1m\x1b[33mwarning\x1b[0m: unused variable: `x`
+ --> exercises/x.rs:2:9
+
+warning: `exercises` (bin \"x\") generated 1 warning
+
+",
+        );
+        assert_eq!(
+            output,
+            b"\x1b[1;31merror\x1b[0m: mismatched types
+ --> exercises/x.rs:3:5
+
+\x1b[4mOutput\x1b[0m
+warning: printed by the exercise
+
+",
+        );
+    }
+
+    #[test]
+    fn expected_output() {
+        let mut output = Vec::new();
+        assert!(matches_expected_output(
+            b"Hello\n\n",
+            "Hello\n",
+            &mut output
+        ));
+        assert!(output.is_empty());
+
+        assert!(!matches_expected_output(
+            b"Hello\nWorld\n",
+            "Hello\nRust",
+            &mut output
+        ));
+        assert!(
+            output.ends_with(b"  Hello\n\x1b[38;5;9m- World\x1b[0m\n\x1b[38;5;10m+ Rust\x1b[0m\n")
+        );
+    }
 }
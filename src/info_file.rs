@@ -1,8 +1,14 @@
 use anyhow::{bail, Context, Error, Result};
-use serde::Deserialize;
-use std::{fs, io::ErrorKind};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind, Write},
+    slice,
+};
 
-use crate::{embedded::EMBEDDED_FILES, exercise::RunnableExercise};
+use crate::{embedded::EMBEDDED_FILES, exercise::RunnableExercise, CURRENT_FORMAT_VERSION};
 
 /// Deserialized from the `info.toml` file.
 #[derive(Deserialize)]
@@ -17,12 +23,107 @@ pub struct ExerciseInfo {
     /// Deny all Clippy warnings.
     #[serde(default)]
     pub strict_clippy: bool,
+    /// Deny compiler warnings when building the exercise.
+    #[serde(default)]
+    pub deny_warnings: bool,
     /// The exercise's hint to be shown to the user on request.
-    pub hint: String,
+    /// Can be omitted if `hint_file` is set.
+    #[serde(default)]
+    pub hint: ExerciseHint,
+    /// Path of a Markdown file (relative to `info.toml`) with the hint.
+    /// Preferred over `hint` which is awkward for long hints with formatting.
+    pub hint_file: Option<String>,
     /// The exercise is already solved. Ignore it when checking that all exercises are unsolved.
     #[serde(default)]
     pub skip_check_unsolved: bool,
+    /// Input written to the stdin of the exercise binary when running it.
+    pub stdin: Option<String>,
+    /// Shell command run before checking the exercise. Its failure fails the check.
+    pub pre_check: Option<String>,
+    /// Shell command run after checking the exercise (e.g. to clean up).
+    /// Its failure is shown in the output but doesn't affect the result of the check.
+    pub post_check: Option<String>,
+    /// The exercise is only done if its output matches this (ignoring trailing whitespace).
+    pub expected_output: Option<String>,
+    /// Optional rating to let beginners skip hard exercises at first.
+    pub difficulty: Option<Difficulty>,
+    /// A challenge that isn't required to finish the exercises.
+    #[serde(default)]
+    pub optional: bool,
+    /// Names of exercises that have to be done before starting this one if the prerequisites
+    /// are enforced. They have to come before this exercise in `info.toml`.
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    /// Crates added to the `[dependencies]` table of `Cargo.toml` for this exercise.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+/// A dependency of an exercise in the form of Cargo.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Dependency {
+    /// Only the version requirement like `"1.0"`.
+    Version(String),
+    /// Like `{ version = "1.0", features = ["derive"] }`.
+    Detailed {
+        version: String,
+        #[serde(default)]
+        features: Vec<String>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Medium => "medium",
+            Self::Hard => "hard",
+        }
+    }
+}
+
+/// Either a single hint or a list of hints to be revealed one at a time.
+/// Defaults to an empty list of hints.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ExerciseHint {
+    Single(String),
+    Progressive(Vec<String>),
+}
+
+impl Default for ExerciseHint {
+    fn default() -> Self {
+        Self::Progressive(Vec::new())
+    }
 }
+
+impl ExerciseHint {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::Single(hint) => vec![hint],
+            Self::Progressive(hints) => hints,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let hints = match self {
+            Self::Single(hint) => slice::from_ref(hint),
+            Self::Progressive(hints) => hints.as_slice(),
+        };
+
+        hints.iter().map(String::as_str)
+    }
+}
+
 #[inline(always)]
 const fn default_true() -> bool {
     true
@@ -70,10 +171,35 @@ impl RunnableExercise for ExerciseInfo {
         self.strict_clippy
     }
 
+    #[inline]
+    fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
     #[inline]
     fn test(&self) -> bool {
         self.test
     }
+
+    #[inline]
+    fn stdin(&self) -> Option<&str> {
+        self.stdin.as_deref()
+    }
+
+    #[inline]
+    fn pre_check(&self) -> Option<&str> {
+        self.pre_check.as_deref()
+    }
+
+    #[inline]
+    fn post_check(&self) -> Option<&str> {
+        self.post_check.as_deref()
+    }
+
+    #[inline]
+    fn expected_output(&self) -> Option<&str> {
+        self.expected_output.as_deref()
+    }
 }
 
 /// The deserialized `info.toml` file.
@@ -113,7 +239,94 @@ impl InfoFile {
 
         Ok(slf)
     }
+
+    /// Fail if the format version is newer than the ones supported by this Rustlings version.
+    pub fn check_format_version(&self) -> Result<()> {
+        if self.format_version > CURRENT_FORMAT_VERSION {
+            bail!(FORMAT_VERSION_HIGHER_ERR);
+        }
+
+        Ok(())
+    }
+}
+
+// An exercise in the JSON output of the `info` command.
+#[derive(Serialize)]
+struct JsonExerciseInfo<'a> {
+    name: &'a str,
+    dir: Option<&'a str>,
+    path: String,
+    solution_path: String,
+    test: bool,
+    strict_clippy: bool,
+    deny_warnings: bool,
+    hints: Vec<&'a str>,
+    hint_file: Option<&'a str>,
+    skip_check_unsolved: bool,
+    stdin: Option<&'a str>,
+    pre_check: Option<&'a str>,
+    post_check: Option<&'a str>,
+    expected_output: Option<&'a str>,
+    difficulty: Option<Difficulty>,
+    optional: bool,
+    prerequisites: &'a [String],
+    dependencies: &'a BTreeMap<String, Dependency>,
+}
+
+#[derive(Serialize)]
+struct JsonInfoFile<'a> {
+    format_version: u8,
+    welcome_message: Option<&'a str>,
+    final_message: Option<&'a str>,
+    exercises: Vec<JsonExerciseInfo<'a>>,
+}
+
+impl InfoFile {
+    /// Print all parsed exercises as a JSON object to stdout (e.g. for external tooling).
+    pub fn print_json(&self) -> Result<()> {
+        let info = JsonInfoFile {
+            format_version: self.format_version,
+            welcome_message: self.welcome_message.as_deref(),
+            final_message: self.final_message.as_deref(),
+            exercises: self
+                .exercises
+                .iter()
+                .map(|exercise_info| JsonExerciseInfo {
+                    name: &exercise_info.name,
+                    dir: exercise_info.dir.as_deref(),
+                    path: exercise_info.path(),
+                    solution_path: exercise_info.sol_path(),
+                    test: exercise_info.test,
+                    strict_clippy: exercise_info.strict_clippy,
+                    deny_warnings: exercise_info.deny_warnings,
+                    hints: exercise_info.hint.iter().collect(),
+                    hint_file: exercise_info.hint_file.as_deref(),
+                    skip_check_unsolved: exercise_info.skip_check_unsolved,
+                    stdin: exercise_info.stdin.as_deref(),
+                    pre_check: exercise_info.pre_check.as_deref(),
+                    post_check: exercise_info.post_check.as_deref(),
+                    expected_output: exercise_info.expected_output.as_deref(),
+                    difficulty: exercise_info.difficulty,
+                    optional: exercise_info.optional,
+                    prerequisites: &exercise_info.prerequisites,
+                    dependencies: &exercise_info.dependencies,
+                })
+                .collect(),
+        };
+
+        let mut stdout = io::stdout().lock();
+        serde_json::to_writer(&mut stdout, &info)
+            .context("Failed to serialize the exercises' info")?;
+        stdout.write_all(b"\n")?;
+
+        Ok(())
+    }
 }
 
+const FORMAT_VERSION_HIGHER_ERR: &str =
+    "The format version specified in the `info.toml` file is higher than the last one supported.
+It is possible that you have an outdated version of Rustlings.
+Try to install the latest Rustlings version first.";
+
 const NO_EXERCISES_ERR: &str = "There are no exercises yet!
 Add at least one exercise before testing.";
@@ -0,0 +1,169 @@
+use anyhow::{bail, Result};
+
+use crate::config::KeysConfig;
+
+/// The keys of the commands in the watch mode.
+#[derive(Clone, Copy)]
+pub struct Keys {
+    pub next: char,
+    pub prev: char,
+    pub back: char,
+    pub skip: char,
+    pub run: char,
+    pub hint: char,
+    pub toggle_hints: char,
+    pub hint_pager: char,
+    pub bookmark: char,
+    pub copy_solution: char,
+    pub peek: char,
+    pub diff: char,
+    pub note: char,
+    pub report: char,
+    pub groups: char,
+    pub stats: char,
+    pub wrap: char,
+    pub pause_watcher: char,
+    pub redraw: char,
+    pub view: char,
+    pub edit: char,
+    pub list: char,
+    pub check: char,
+    pub check_all: char,
+    pub next_failure: char,
+    pub reset: char,
+    pub undo: char,
+    pub reload: char,
+    pub quit: char,
+}
+
+impl Keys {
+    const DEFAULT: Self = Self {
+        next: 'n',
+        prev: 'p',
+        back: 'B',
+        skip: 's',
+        run: 'r',
+        hint: 'h',
+        toggle_hints: 'O',
+        hint_pager: 'H',
+        bookmark: 'b',
+        copy_solution: 'y',
+        peek: 'P',
+        diff: 'd',
+        note: 'N',
+        report: 'I',
+        groups: 'g',
+        stats: 't',
+        wrap: 'w',
+        pause_watcher: 'W',
+        redraw: 'z',
+        view: 'v',
+        edit: 'e',
+        list: 'l',
+        check: 'C',
+        check_all: 'c',
+        next_failure: 'f',
+        reset: 'x',
+        undo: 'u',
+        reload: 'R',
+        quit: 'q',
+    };
+
+    /// Build the keys from the config file. Missing keys are taken from the defaults.
+    /// Fails if a key is bound to more than one command.
+    pub fn build(config: &KeysConfig) -> Result<Self> {
+        let mut keys = Self::DEFAULT;
+
+        let mut bindings = [
+            (&mut keys.next, config.next, "next"),
+            (&mut keys.prev, config.prev, "prev"),
+            (&mut keys.back, config.back, "back"),
+            (&mut keys.skip, config.skip, "skip"),
+            (&mut keys.run, config.run, "run"),
+            (&mut keys.hint, config.hint, "hint"),
+            (&mut keys.toggle_hints, config.toggle_hints, "toggle_hints"),
+            (&mut keys.hint_pager, config.hint_pager, "hint_pager"),
+            (&mut keys.bookmark, config.bookmark, "bookmark"),
+            (
+                &mut keys.copy_solution,
+                config.copy_solution,
+                "copy_solution",
+            ),
+            (&mut keys.peek, config.peek, "peek"),
+            (&mut keys.diff, config.diff, "diff"),
+            (&mut keys.note, config.note, "note"),
+            (&mut keys.report, config.report, "report"),
+            (&mut keys.groups, config.groups, "groups"),
+            (&mut keys.stats, config.stats, "stats"),
+            (&mut keys.wrap, config.wrap, "wrap"),
+            (
+                &mut keys.pause_watcher,
+                config.pause_watcher,
+                "pause_watcher",
+            ),
+            (&mut keys.redraw, config.redraw, "redraw"),
+            (&mut keys.view, config.view, "view"),
+            (&mut keys.edit, config.edit, "edit"),
+            (&mut keys.list, config.list, "list"),
+            (&mut keys.check, config.check, "check"),
+            (&mut keys.check_all, config.check_all, "check_all"),
+            (&mut keys.next_failure, config.next_failure, "next_failure"),
+            (&mut keys.reset, config.reset, "reset"),
+            (&mut keys.undo, config.undo, "undo"),
+            (&mut keys.reload, config.reload, "reload"),
+            (&mut keys.quit, config.quit, "quit"),
+        ];
+
+        for (key, config_key, field) in &mut bindings {
+            if let Some(config_key) = config_key {
+                if config_key.is_whitespace() || config_key.is_control() {
+                    bail!("Invalid key {config_key:?} of `keys.{field}` in the config file");
+                }
+                **key = *config_key;
+            }
+        }
+
+        // There are only a few keys.
+        for (ind, (key, _, field)) in bindings.iter().enumerate() {
+            if let Some((_, _, other_field)) = bindings[ind + 1..]
+                .iter()
+                .find(|(other_key, _, _)| **other_key == **key)
+            {
+                bail!("The key `{key}` is bound to both `keys.{field}` and `keys.{other_field}`. Check the config file");
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let keys = Keys::build(&KeysConfig {
+            next: Some('j'),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(keys.next, 'j');
+        assert_eq!(keys.quit, 'q');
+
+        // `h` is already bound to `hint`.
+        assert!(Keys::build(&KeysConfig {
+            next: Some('h'),
+            ..Default::default()
+        })
+        .is_err());
+
+        // The old key of a remapped command is free again.
+        assert!(Keys::build(&KeysConfig {
+            next: Some('h'),
+            hint: Some('n'),
+            ..Default::default()
+        })
+        .is_ok());
+    }
+}
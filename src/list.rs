@@ -16,12 +16,14 @@ use crate::app_state::AppState;
 
 use self::state::{Filter, ListState};
 
+mod fuzzy;
 mod scroll_state;
 mod state;
 
 fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()> {
     let mut list_state = ListState::build(app_state, stdout)?;
     let mut is_searching = false;
+    let mut is_going_to = false;
 
     loop {
         match event::read().context("Failed to read terminal event")? {
@@ -35,10 +37,12 @@ fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()>
 
                 if is_searching {
                     match key.code {
-                        KeyCode::Esc | KeyCode::Enter => {
+                        KeyCode::Esc => {
                             is_searching = false;
-                            list_state.search_query.clear();
+                            list_state.clear_search_query();
                         }
+                        // Keep only showing the search results.
+                        KeyCode::Enter => is_searching = false,
                         KeyCode::Char(c) => {
                             list_state.search_query.push(c);
                             list_state.apply_search_query();
@@ -54,11 +58,40 @@ fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()>
                     continue;
                 }
 
+                if is_going_to {
+                    match key.code {
+                        KeyCode::Esc => {
+                            is_going_to = false;
+                            list_state.goto_query.clear();
+                        }
+                        KeyCode::Enter => {
+                            is_going_to = false;
+                            let jumped = list_state.goto_exercise()?;
+                            list_state.goto_query.clear();
+                            if jumped {
+                                return Ok(());
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            list_state.goto_query.push(c);
+                            list_state.show_goto_query();
+                        }
+                        KeyCode::Backspace => {
+                            list_state.goto_query.pop();
+                            list_state.show_goto_query();
+                        }
+                        _ => continue,
+                    }
+
+                    list_state.draw(stdout)?;
+                    continue;
+                }
+
                 match key.code {
                     KeyCode::Char('q') => return Ok(()),
                     KeyCode::Down | KeyCode::Char('j') => list_state.select_next(),
                     KeyCode::Up | KeyCode::Char('k') => list_state.select_previous(),
-                    KeyCode::Home | KeyCode::Char('g') => list_state.select_first(),
+                    KeyCode::Home => list_state.select_first(),
                     KeyCode::End | KeyCode::Char('G') => list_state.select_last(),
                     KeyCode::Char('d') => {
                         if list_state.filter() == Filter::Done {
@@ -82,18 +115,39 @@ fn handle_list(app_state: &mut AppState, stdout: &mut StdoutLock) -> Result<()>
                             );
                         }
                     }
+                    KeyCode::Char('o') => {
+                        if list_state.filter() == Filter::Pending {
+                            list_state.set_filter(Filter::None);
+                            list_state.message.push_str("Showing done exercises");
+                        } else {
+                            list_state.set_filter(Filter::Pending);
+                            list_state
+                                .message
+                                .push_str("Hiding done exercises │ Press o again to show them");
+                        }
+                    }
                     KeyCode::Char('r') => list_state.reset_selected()?,
+                    KeyCode::Char('b') => {
+                        if !list_state.select_next_bookmarked() {
+                            list_state.message.push_str("No bookmarked exercise found!");
+                        }
+                    }
                     KeyCode::Char('c') => {
                         if list_state.selected_to_current_exercise()? {
                             return Ok(());
                         }
                     }
+                    KeyCode::Char('i') => list_state.toggle_details(stdout)?,
+                    KeyCode::Char('g') => {
+                        is_going_to = true;
+                        list_state.show_goto_query();
+                    }
                     KeyCode::Char('s' | '/') => {
                         is_searching = true;
                         list_state.apply_search_query();
                     }
-                    // Redraw to remove the message.
-                    KeyCode::Esc => (),
+                    // Clear the search query and redraw to remove the message.
+                    KeyCode::Esc => list_state.clear_search_query(),
                     _ => continue,
                 }
             }
@@ -133,3 +187,22 @@ pub fn list(app_state: &mut AppState) -> Result<()> {
 
     res
 }
+
+/// Print one line per exercise with its number, name and status without a terminal UI.
+/// The columns are separated by tabs for piping into other tools.
+pub fn print(app_state: &AppState, pending_only: bool) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    for (exercise_ind, exercise) in app_state.exercises().iter().enumerate() {
+        if pending_only && exercise.done {
+            continue;
+        }
+
+        let status = if exercise.done { "done" } else { "pending" };
+        writeln!(stdout, "{}\t{}\t{status}", exercise_ind + 1, exercise.name)?;
+    }
+
+    stdout
+        .flush()
+        .context("Failed to write the list of exercises")
+}
@@ -0,0 +1,143 @@
+// Scores of the fuzzy matching. Consecutive matches and matches at the start of a word are
+// preferred. Skipped characters between matches are penalized.
+const SCORE_MATCH: i32 = 16;
+const BONUS_CONSECUTIVE: i32 = 8;
+const BONUS_BOUNDARY: i32 = 8;
+const PENALTY_GAP: i32 = 1;
+
+/// A successful fuzzy match of a query in a haystack.
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i32,
+    /// The byte indices of the matched characters in the haystack.
+    pub positions: Vec<usize>,
+}
+
+// Remove the leading zeros of numbers in the query so that `iter02` matches `iterators2`.
+fn normalize_query(query: &str) -> Vec<u8> {
+    let query = query.as_bytes();
+    let mut normalized = Vec::with_capacity(query.len());
+    let mut in_leading_zeros = false;
+
+    for (ind, &c) in query.iter().enumerate() {
+        if c.is_ascii_digit() {
+            if ind == 0 || !query[ind - 1].is_ascii_digit() {
+                in_leading_zeros = true;
+            }
+
+            // Keep the last digit of a number like `0` in `00`.
+            if in_leading_zeros && c == b'0' && query.get(ind + 1).is_some_and(u8::is_ascii_digit) {
+                continue;
+            }
+            in_leading_zeros = false;
+        }
+
+        normalized.push(c.to_ascii_lowercase());
+    }
+
+    normalized
+}
+
+// Whether a match at the byte index `ind` is at the start of a word like `s` or `1` in
+// `move_semantics1`.
+fn is_boundary(haystack: &[u8], ind: usize) -> bool {
+    let Some(&prev) = ind
+        .checked_sub(1)
+        .and_then(|prev_ind| haystack.get(prev_ind))
+    else {
+        return true;
+    };
+    let c = haystack[ind];
+
+    !prev.is_ascii_alphanumeric() || (c.is_ascii_digit() && !prev.is_ascii_digit())
+}
+
+// Greedily match the query starting with its first character at the byte index `start`.
+fn match_from(haystack: &[u8], query: &[u8], start: usize) -> Option<FuzzyMatch> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut haystack_ind = start;
+
+    for &c in query {
+        let offset = haystack[haystack_ind..]
+            .iter()
+            .position(|h| h.to_ascii_lowercase() == c)?;
+        let ind = haystack_ind + offset;
+
+        score += SCORE_MATCH;
+        if is_boundary(haystack, ind) {
+            score += BONUS_BOUNDARY;
+        }
+        if let Some(&last) = positions.last() {
+            if ind == last + 1 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * (ind - last - 1) as i32;
+            }
+        }
+
+        positions.push(ind);
+        haystack_ind = ind + 1;
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Match the characters of `query` in order but not necessarily consecutively in `haystack`.
+/// The matching is ASCII case-insensitive.
+/// Returns the best match or `None` if not all characters of the query are found.
+pub fn fuzzy_match(haystack: &str, query: &str) -> Option<FuzzyMatch> {
+    // Exercise names are ASCII. Matching single bytes of other characters wouldn't make sense.
+    if !query.is_ascii() {
+        return None;
+    }
+
+    let query = normalize_query(query);
+    let Some(&first) = query.first() else {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    };
+    let haystack = haystack.as_bytes();
+
+    // Try every occurrence of the first character since the first one isn't always the best.
+    haystack
+        .iter()
+        .enumerate()
+        .filter(|(_, h)| h.to_ascii_lowercase() == first)
+        .filter_map(|(start, _)| match_from(haystack, &query, start))
+        .max_by(|a, b| {
+            a.score
+                .cmp(&b.score)
+                .then(b.positions[0].cmp(&a.positions[0]))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy() {
+        assert!(fuzzy_match("iterators2", "iter02").is_some());
+        assert!(fuzzy_match("iterators2", "iter20").is_none());
+        assert!(fuzzy_match("strings0", "strings00").is_some());
+        assert!(fuzzy_match("iterators2", "").is_some());
+        assert!(fuzzy_match("iterators2", "xyz").is_none());
+
+        let m = fuzzy_match("move_semantics1", "MS1").unwrap();
+        assert_eq!(m.positions, [0, 5, 14]);
+
+        // The consecutive match is preferred.
+        assert_eq!(
+            fuzzy_match("vecs_vec1", "vec1").unwrap().positions,
+            [5, 6, 7, 8]
+        );
+
+        // Closer matches are ranked higher.
+        let exact = fuzzy_match("strings1", "str1").unwrap();
+        let spread = fuzzy_match("structs_and_traits1", "str1").unwrap();
+        assert!(exact.score > spread.score);
+    }
+}
@@ -8,17 +8,20 @@ use crossterm::{
     QueueableCommand,
 };
 use std::{
+    cmp::Reverse,
     fmt::Write as _,
+    fs,
     io::{self, StdoutLock, Write},
 };
 
 use crate::{
     app_state::AppState,
     exercise::Exercise,
+    info_file::Difficulty,
     term::{progress_bar, CountedWrite, MaxLenWriter},
 };
 
-use super::scroll_state::ScrollState;
+use super::{fuzzy::fuzzy_match, scroll_state::ScrollState};
 
 const COL_SPACING: usize = 2;
 const SELECTED_ROW_ATTRIBUTES: Attributes = Attributes::none()
@@ -32,6 +35,18 @@ fn next_ln(stdout: &mut StdoutLock) -> io::Result<()> {
     Ok(())
 }
 
+// Return the byte index of the first ASCII case-insensitive occurrence of `needle`.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Filter {
     Done,
@@ -39,10 +54,18 @@ pub enum Filter {
     None,
 }
 
+// The optional columns with details of an exercise.
+struct ExerciseDetails {
+    n_lines: Option<usize>,
+    compiles: bool,
+}
+
 pub struct ListState<'a> {
     /// Footer message to be displayed if not empty.
     pub message: String,
     pub search_query: String,
+    /// Exercise number or name typed after pressing `g`.
+    pub goto_query: String,
     app_state: &'a mut AppState,
     scroll_state: ScrollState,
     name_col_padding: Vec<u8>,
@@ -51,6 +74,11 @@ pub struct ListState<'a> {
     term_width: u16,
     term_height: u16,
     show_footer: bool,
+    show_details: bool,
+    // Only if any exercise has a difficulty.
+    show_difficulty: bool,
+    // Computed when the details are shown for the first time.
+    details: Vec<ExerciseDetails>,
 }
 
 impl<'a> ListState<'a> {
@@ -74,6 +102,7 @@ impl<'a> ListState<'a> {
         let filter = Filter::None;
         let n_rows_with_filter = app_state.exercises().len();
         let selected = app_state.current_exercise_ind();
+        let show_difficulty = app_state.has_difficulties();
 
         let (width, height) = terminal::size().context("Failed to get the terminal size")?;
         let scroll_state = ScrollState::new(n_rows_with_filter, Some(selected), 5);
@@ -81,6 +110,7 @@ impl<'a> ListState<'a> {
         let mut slf = Self {
             message: String::with_capacity(128),
             search_query: String::new(),
+            goto_query: String::new(),
             app_state,
             scroll_state,
             name_col_padding,
@@ -90,6 +120,9 @@ impl<'a> ListState<'a> {
             term_width: 0,
             term_height: 0,
             show_footer: true,
+            show_details: false,
+            show_difficulty,
+            details: Vec::new(),
         };
 
         slf.set_term_size(width, height);
@@ -117,70 +150,125 @@ impl<'a> ListState<'a> {
         );
     }
 
-    fn draw_exercise_name(&self, writer: &mut MaxLenWriter, exercise: &Exercise) -> io::Result<()> {
-        if !self.search_query.is_empty() {
-            if let Some((pre_highlight, highlight, post_highlight)) = exercise
-                .name
-                .find(&self.search_query)
-                .and_then(|ind| exercise.name.split_at_checked(ind))
-                .and_then(|(pre_highlight, rest)| {
-                    rest.split_at_checked(self.search_query.len())
-                        .map(|x| (pre_highlight, x.0, x.1))
-                })
-            {
-                writer.write_str(pre_highlight)?;
-                writer.stdout.queue(SetForegroundColor(Color::Magenta))?;
-                writer.write_str(highlight)?;
-                writer.stdout.queue(SetForegroundColor(Color::Reset))?;
-                return writer.write_str(post_highlight);
-            }
+    fn filter_matches(&self, exercise: &Exercise) -> bool {
+        match self.filter {
+            Filter::Done => exercise.done,
+            Filter::Pending => !exercise.done,
+            Filter::None => true,
+        }
+    }
+
+    // The score of the search query matching the exercise or `None` if it doesn't match.
+    // The query is fuzzy matched against the name. Exercises with only a path containing the
+    // query are ranked last.
+    fn search_score(&self, exercise: &Exercise) -> Option<i32> {
+        if let Some(fuzzy_match) = fuzzy_match(exercise.name, &self.search_query) {
+            return Some(fuzzy_match.score);
         }
 
-        writer.write_str(exercise.name)
+        find_ignore_ascii_case(exercise.path, &self.search_query).map(|_| i32::MIN)
+    }
+
+    // The exercises shown with the current filter and search query ranked by the match score.
+    fn shown_exercises(&self) -> impl Iterator<Item = (usize, &Exercise)> {
+        let mut shown = self
+            .app_state
+            .exercises()
+            .iter()
+            .enumerate()
+            .filter(|(_, exercise)| self.filter_matches(exercise))
+            .filter_map(|(ind, exercise)| Some((self.search_score(exercise)?, ind, exercise)))
+            .collect::<Vec<_>>();
+        // Stable to keep the order of the exercises with the same score.
+        shown.sort_by_key(|(score, _, _)| Reverse(*score));
+
+        shown.into_iter().map(|(_, ind, exercise)| (ind, exercise))
     }
 
-    fn draw_rows(
+    fn draw_exercise_name(
         &self,
-        stdout: &mut StdoutLock,
-        filtered_exercises: impl Iterator<Item = (usize, &'a Exercise)>,
-    ) -> io::Result<usize> {
+        writer: &mut MaxLenWriter<StdoutLock>,
+        exercise: &Exercise,
+    ) -> io::Result<()> {
+        let theme = self.app_state.theme();
+        let Some(fuzzy_match) = fuzzy_match(exercise.name, &self.search_query) else {
+            return writer.write_str(exercise.name);
+        };
+
+        // Highlight the runs of consecutive matched characters.
+        let mut written = 0;
+        let mut positions = fuzzy_match.positions.into_iter().peekable();
+        while let Some(start) = positions.next() {
+            let mut end = start + 1;
+            while positions.next_if_eq(&end).is_some() {
+                end += 1;
+            }
+
+            writer.write_str(&exercise.name[written..start])?;
+            theme.queue_style(writer.stdout, SetForegroundColor(Color::Magenta))?;
+            writer.write_str(&exercise.name[start..end])?;
+            theme.queue_style(writer.stdout, SetForegroundColor(Color::Reset))?;
+            written = end;
+        }
+
+        writer.write_str(&exercise.name[written..])
+    }
+
+    fn draw_rows(&self, stdout: &mut StdoutLock) -> io::Result<usize> {
+        let theme = self.app_state.theme();
         let current_exercise_ind = self.app_state.current_exercise_ind();
         let row_offset = self.scroll_state.offset();
         let mut n_displayed_rows = 0;
 
-        for (exercise_ind, exercise) in filtered_exercises
+        for (exercise_ind, exercise) in self
+            .shown_exercises()
             .skip(row_offset)
             .take(self.scroll_state.max_n_rows_to_display())
         {
             let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
 
             if self.scroll_state.selected() == Some(row_offset + n_displayed_rows) {
-                // The crab emoji has the width of two ascii chars.
-                writer.add_to_len(2);
-                writer.stdout.write_all("🦀".as_bytes())?;
-                writer
-                    .stdout
-                    .queue(SetAttributes(SELECTED_ROW_ATTRIBUTES))?;
+                if theme.a11y() {
+                    writer.write_ascii(b"> ")?;
+                } else {
+                    // The crab emoji has the width of two ascii chars.
+                    writer.add_to_len(2);
+                    writer.stdout.write_all("🦀".as_bytes())?;
+                }
+                theme.queue_style(writer.stdout, SetAttributes(SELECTED_ROW_ATTRIBUTES))?;
             } else {
                 writer.write_ascii(b"  ")?;
             }
 
             if exercise_ind == current_exercise_ind {
-                writer.stdout.queue(SetForegroundColor(Color::Red))?;
+                theme.queue_style(writer.stdout, SetForegroundColor(Color::Red))?;
                 writer.write_ascii(b">>>>>>>  ")?;
             } else {
                 writer.write_ascii(b"         ")?;
             }
 
             if exercise.done {
-                writer.stdout.queue(SetForegroundColor(Color::Green))?;
+                theme.queue_style(writer.stdout, SetForegroundColor(Color::Green))?;
                 writer.write_ascii(b"DONE   ")?;
+            } else if self.app_state.is_locked(exercise_ind) {
+                theme.queue_style(writer.stdout, SetForegroundColor(Color::DarkGrey))?;
+                writer.write_ascii(b"LOCKED ")?;
             } else {
-                writer.stdout.queue(SetForegroundColor(Color::Yellow))?;
+                theme.queue_style(writer.stdout, SetForegroundColor(Color::Yellow))?;
                 writer.write_ascii(b"PENDING")?;
             }
-            writer.stdout.queue(SetForegroundColor(Color::Reset))?;
-            writer.write_ascii(b"  ")?;
+            theme.queue_style(writer.stdout, SetForegroundColor(Color::Reset))?;
+
+            if self.show_difficulty {
+                let difficulty = exercise.difficulty.map_or("unrated", Difficulty::as_str);
+                writer.write_str(&format!("  {difficulty:<10}"))?;
+            }
+
+            if exercise.bookmarked {
+                writer.write_str("  ★ ")?;
+            } else {
+                writer.write_ascii(b"    ")?;
+            }
 
             self.draw_exercise_name(&mut writer, exercise)?;
 
@@ -191,13 +279,27 @@ impl<'a> ListState<'a> {
             if self.app_state.vs_code() {
                 writer.write_str(exercise.path)?;
             } else {
-                exercise.terminal_file_link(&mut writer)?;
+                exercise.terminal_file_link(&mut writer, theme)?;
             }
 
             writer.write_ascii(&self.path_col_padding[exercise.path.len()..])?;
 
+            if let Some(details) = self.details.get(exercise_ind).filter(|_| self.show_details) {
+                match details.n_lines {
+                    Some(n_lines) => writer.write_str(&format!("  {n_lines:>5}  "))?,
+                    None => writer.write_ascii(b"      ?  ")?,
+                }
+
+                if details.compiles {
+                    writer.write_ascii(b"yes")?;
+                } else {
+                    theme.queue_style(writer.stdout, SetForegroundColor(Color::Red))?;
+                    writer.write_ascii(b"no")?;
+                }
+            }
+
             next_ln(stdout)?;
-            stdout.queue(ResetColor)?;
+            theme.queue_style(stdout, ResetColor)?;
             n_displayed_rows += 1;
         }
 
@@ -210,34 +312,38 @@ impl<'a> ListState<'a> {
         }
 
         stdout.queue(BeginSynchronizedUpdate)?.queue(MoveTo(0, 0))?;
+        let theme = self.app_state.theme();
 
         // Header
         let mut writer = MaxLenWriter::new(stdout, self.term_width as usize);
-        writer.write_ascii(b"  Current  State    Name")?;
+        if self.show_difficulty {
+            writer.write_ascii(b"  Current  State    Difficulty    Name")?;
+        } else {
+            writer.write_ascii(b"  Current  State      Name")?;
+        }
         writer.write_ascii(&self.name_col_padding[4..])?;
         writer.write_ascii(b"Path")?;
+        if self.show_details {
+            writer.write_ascii(&self.path_col_padding[4..])?;
+            writer.write_ascii(b"  Lines  Compiles")?;
+        }
         next_ln(stdout)?;
 
         // Rows
-        let iter = self.app_state.exercises().iter().enumerate();
-        let n_displayed_rows = match self.filter {
-            Filter::Done => self.draw_rows(stdout, iter.filter(|(_, exercise)| exercise.done))?,
-            Filter::Pending => {
-                self.draw_rows(stdout, iter.filter(|(_, exercise)| !exercise.done))?
-            }
-            Filter::None => self.draw_rows(stdout, iter)?,
-        };
+        let n_displayed_rows = self.draw_rows(stdout)?;
 
         for _ in 0..self.scroll_state.max_n_rows_to_display() - n_displayed_rows {
             next_ln(stdout)?;
         }
 
         if self.show_footer {
+            let (n_core_done, n_core) = self.app_state.core_progress();
             progress_bar(
                 &mut MaxLenWriter::new(stdout, self.term_width as usize),
-                self.app_state.n_done(),
-                self.app_state.exercises().len() as u16,
+                n_core_done,
+                n_core,
                 self.term_width,
+                theme,
             )?;
             next_ln(stdout)?;
 
@@ -245,11 +351,12 @@ impl<'a> ListState<'a> {
             if self.message.is_empty() {
                 // Help footer message
                 if self.scroll_state.selected().is_some() {
-                    writer.write_str("↓/j ↑/k home/g end/G | <c>ontinue at | <r>eset exercise")?;
+                    writer.write_str("↓/j ↑/k home end/G | <c>ontinue at | <r>eset exercise")?;
                     next_ln(stdout)?;
                     writer = MaxLenWriter::new(stdout, self.term_width as usize);
 
-                    writer.write_ascii(b"<s>earch | filter ")?;
+                    writer
+                        .write_ascii(b"<s>earch | <g>oto | next <b>ookmark | <i>nfo | filter ")?;
                 } else {
                     // Nothing selected (and nothing shown), so only display filter and quit.
                     writer.write_ascii(b"filter ")?;
@@ -257,31 +364,27 @@ impl<'a> ListState<'a> {
 
                 match self.filter {
                     Filter::Done => {
-                        writer
-                            .stdout
-                            .queue(SetForegroundColor(Color::Magenta))?
-                            .queue(SetAttribute(Attribute::Underlined))?;
+                        theme.queue_style(writer.stdout, SetForegroundColor(Color::Magenta))?;
+                        theme.queue_style(writer.stdout, SetAttribute(Attribute::Underlined))?;
                         writer.write_ascii(b"<d>one")?;
-                        writer.stdout.queue(ResetColor)?;
+                        theme.queue_style(writer.stdout, ResetColor)?;
                         writer.write_ascii(b"/<p>ending")?;
                     }
                     Filter::Pending => {
                         writer.write_ascii(b"<d>one/")?;
-                        writer
-                            .stdout
-                            .queue(SetForegroundColor(Color::Magenta))?
-                            .queue(SetAttribute(Attribute::Underlined))?;
+                        theme.queue_style(writer.stdout, SetForegroundColor(Color::Magenta))?;
+                        theme.queue_style(writer.stdout, SetAttribute(Attribute::Underlined))?;
                         writer.write_ascii(b"<p>ending")?;
-                        writer.stdout.queue(ResetColor)?;
+                        theme.queue_style(writer.stdout, ResetColor)?;
                     }
                     Filter::None => writer.write_ascii(b"<d>one/<p>ending")?,
                 }
 
                 writer.write_ascii(b" | <q>uit list")?;
             } else {
-                writer.stdout.queue(SetForegroundColor(Color::Magenta))?;
+                theme.queue_style(writer.stdout, SetForegroundColor(Color::Magenta))?;
                 writer.write_str(&self.message)?;
-                stdout.queue(ResetColor)?;
+                theme.queue_style(stdout, ResetColor)?;
                 next_ln(stdout)?;
             }
 
@@ -292,22 +395,7 @@ impl<'a> ListState<'a> {
     }
 
     fn update_rows(&mut self) {
-        let n_rows = match self.filter {
-            Filter::Done => self
-                .app_state
-                .exercises()
-                .iter()
-                .filter(|exercise| exercise.done)
-                .count(),
-            Filter::Pending => self
-                .app_state
-                .exercises()
-                .iter()
-                .filter(|exercise| !exercise.done)
-                .count(),
-            Filter::None => self.app_state.exercises().len(),
-        };
-
+        let n_rows = self.shown_exercises().count();
         self.scroll_state.set_n_rows(n_rows);
     }
 
@@ -316,9 +404,24 @@ impl<'a> ListState<'a> {
         self.filter
     }
 
+    // Keep the selected exercise selected if it is still shown.
+    // Otherwise, select the next shown exercise after it.
     pub fn set_filter(&mut self, filter: Filter) {
+        let selected_exercise_ind = self
+            .scroll_state
+            .selected()
+            .and_then(|selected| self.selected_to_exercise_ind(selected).ok());
+
         self.filter = filter;
         self.update_rows();
+
+        let row = selected_exercise_ind.and_then(|selected_exercise_ind| {
+            self.shown_exercises()
+                .position(|(ind, _)| ind >= selected_exercise_ind)
+        });
+        if let Some(row) = row {
+            self.scroll_state.set_selected(row);
+        }
     }
 
     #[inline]
@@ -341,28 +444,37 @@ impl<'a> ListState<'a> {
         self.scroll_state.select_last();
     }
 
+    // Select the next bookmarked exercise after the selected one.
+    // Return `false` if no shown exercise is bookmarked.
+    pub fn select_next_bookmarked(&mut self) -> bool {
+        let start = self
+            .scroll_state
+            .selected()
+            .map_or(0, |selected| selected + 1);
+        let bookmarked_rows = self
+            .shown_exercises()
+            .enumerate()
+            .filter(|(_, (_, exercise))| exercise.bookmarked)
+            .map(|(row, _)| row)
+            .collect::<Vec<_>>();
+
+        let Some(&row) = bookmarked_rows
+            .iter()
+            .find(|row| **row >= start)
+            .or_else(|| bookmarked_rows.first())
+        else {
+            return false;
+        };
+
+        self.scroll_state.set_selected(row);
+        true
+    }
+
     fn selected_to_exercise_ind(&self, selected: usize) -> Result<usize> {
-        match self.filter {
-            Filter::Done => self
-                .app_state
-                .exercises()
-                .iter()
-                .enumerate()
-                .filter(|(_, exercise)| exercise.done)
-                .nth(selected)
-                .context("Invalid selection index")
-                .map(|(ind, _)| ind),
-            Filter::Pending => self
-                .app_state
-                .exercises()
-                .iter()
-                .enumerate()
-                .filter(|(_, exercise)| !exercise.done)
-                .nth(selected)
-                .context("Invalid selection index")
-                .map(|(ind, _)| ind),
-            Filter::None => Ok(selected),
-        }
+        self.shown_exercises()
+            .nth(selected)
+            .context("Invalid selection index")
+            .map(|(ind, _)| ind)
     }
 
     pub fn reset_selected(&mut self) -> Result<()> {
@@ -382,31 +494,124 @@ impl<'a> ListState<'a> {
         Ok(())
     }
 
+    /// Show or hide the line count of every exercise file and whether the exercise compiles.
+    pub fn toggle_details(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.show_details = !self.show_details;
+
+        if self.show_details && self.details.is_empty() {
+            self.message
+                .push_str("Compiling all exercises. Please wait…");
+            self.draw(stdout)?;
+            self.message.clear();
+
+            let compiles = self.app_state.compile_all_exercises()?;
+            self.details = self
+                .app_state
+                .exercises()
+                .iter()
+                .zip(compiles)
+                .map(|(exercise, compiles)| ExerciseDetails {
+                    n_lines: fs::read_to_string(exercise.path)
+                        .ok()
+                        .map(|content| content.lines().count()),
+                    compiles,
+                })
+                .collect();
+        }
+
+        Ok(())
+    }
+
+    // Only show the exercises matching the search query and select the first one.
     pub fn apply_search_query(&mut self) {
         self.message.push_str("search:");
         self.message.push_str(&self.search_query);
         self.message.push('|');
 
+        self.update_rows();
+
         if self.search_query.is_empty() {
             return;
         }
 
-        let is_search_result = |exercise: &Exercise| exercise.name.contains(&self.search_query);
-        let mut iter = self.app_state.exercises().iter();
-        let ind = match self.filter {
-            Filter::None => iter.position(is_search_result),
-            Filter::Done => iter
-                .filter(|exercise| exercise.done)
-                .position(is_search_result),
-            Filter::Pending => iter
-                .filter(|exercise| !exercise.done)
-                .position(is_search_result),
-        };
+        if self.scroll_state.selected().is_some() {
+            self.scroll_state.select_first();
+        } else {
+            self.message.push_str(" (not found)");
+        }
+    }
 
-        match ind {
-            Some(exercise_ind) => self.scroll_state.set_selected(exercise_ind),
-            None => self.message.push_str(" (not found)"),
+    pub fn clear_search_query(&mut self) {
+        self.search_query.clear();
+        self.update_rows();
+    }
+
+    pub fn show_goto_query(&mut self) {
+        self.message.push_str("goto (number or name):");
+        self.message.push_str(&self.goto_query);
+        self.message.push('|');
+
+        let query = self.goto_query.trim();
+        if !query.is_empty() && query.parse::<usize>().is_err() {
+            match self.goto_name_match(query) {
+                Some(ind) => {
+                    self.message.push_str(" → ");
+                    self.message.push_str(self.app_state.exercises()[ind].name);
+                }
+                None => self.message.push_str(" (not found)"),
+            }
+        }
+    }
+
+    // The index of the exercise with the name `query` or the best fuzzy match of it.
+    fn goto_name_match(&self, query: &str) -> Option<usize> {
+        let exercises = self.app_state.exercises();
+        if let Some(ind) = exercises.iter().position(|exercise| exercise.name == query) {
+            return Some(ind);
+        }
+
+        exercises
+            .iter()
+            .enumerate()
+            .filter_map(|(ind, exercise)| Some((fuzzy_match(exercise.name, query)?.score, ind)))
+            // The first exercise wins on equal scores.
+            .max_by(|(a, a_ind), (b, b_ind)| a.cmp(b).then(b_ind.cmp(a_ind)))
+            .map(|(_, ind)| ind)
+    }
+
+    // Set the current exercise to the one with the number (starting at 1) or name in the goto query.
+    // Return `true` if the current exercise was changed.
+    pub fn goto_exercise(&mut self) -> Result<bool> {
+        let query = self.goto_query.trim();
+        let n_exercises = self.app_state.exercises().len();
+
+        let exercise_ind = if let Ok(number) = query.parse::<usize>() {
+            if number == 0 || number > n_exercises {
+                write!(
+                    self.message,
+                    "Invalid exercise number {number}. Expected a number from 1 to {n_exercises}",
+                )?;
+                return Ok(false);
+            }
+
+            number - 1
+        } else if let Some(ind) = self.goto_name_match(query) {
+            ind
+        } else {
+            write!(
+                self.message,
+                "No exercise with a name matching `{query}` found!"
+            )?;
+            return Ok(false);
+        };
+        if let Some(locked_message) = self.app_state.locked_message(exercise_ind) {
+            self.message.push_str(&locked_message);
+            return Ok(false);
         }
+
+        self.app_state.set_current_exercise_ind(exercise_ind)?;
+
+        Ok(true)
     }
 
     // Return `true` if there was something to select.
@@ -417,6 +622,10 @@ impl<'a> ListState<'a> {
         };
 
         let exercise_ind = self.selected_to_exercise_ind(selected)?;
+        if let Some(locked_message) = self.app_state.locked_message(exercise_ind) {
+            self.message.push_str(&locked_message);
+            return Ok(false);
+        }
         self.app_state.set_current_exercise_ind(exercise_ind)?;
 
         Ok(true)
@@ -1,26 +1,49 @@
 use anyhow::{bail, Context, Result};
-use app_state::StateFileStatus;
+use app_state::{ExerciseFilter, StateFileStatus};
 use clap::{Parser, Subcommand};
 use std::{
-    io::{self, IsTerminal, Write},
-    path::Path,
+    env,
+    fs::File,
+    io::{self, ErrorKind, IsTerminal, Write},
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
     process::ExitCode,
+    time::Duration,
 };
 use term::{clear_terminal, press_enter_prompt};
 
-use self::{app_state::AppState, dev::DevCommands, info_file::InfoFile};
+use self::{
+    app_state::AppState,
+    cmd::{CmdRunner, DEFAULT_MAX_OUTPUT_LEN, DEFAULT_TIMEOUT},
+    config::Config,
+    dev::DevCommands,
+    info_file::{Difficulty, InfoFile},
+    keys::Keys,
+    messages::Messages,
+    report::ReportFormat,
+    theme::{Completion, Theme},
+    watch::{EventStream, WatchOptions, DEFAULT_DEBOUNCE_DURATION, DEFAULT_PEEK_LINES},
+};
 
 mod app_state;
 mod cargo_toml;
 mod cmd;
+mod config;
 mod dev;
+mod diff;
 mod embedded;
+mod error_notes;
 mod exercise;
 mod info_file;
 mod init;
+mod keys;
 mod list;
+mod messages;
+mod report;
 mod run;
+mod rust_analyzer;
 mod term;
+mod theme;
 mod watch;
 
 const CURRENT_FORMAT_VERSION: u8 = 1;
@@ -31,10 +54,112 @@ const CURRENT_FORMAT_VERSION: u8 = 1;
 struct Args {
     #[command(subcommand)]
     command: Option<Subcommands>,
-    /// Manually run the current exercise using `r` in the watch mode.
+    /// Only run the current exercise manually using `r` in the watch mode instead of watching files.
     /// Only use this if Rustlings fails to detect exercise file changes.
+    /// The default can be set with `manual_run` in the config file.
     #[arg(long)]
     manual_run: bool,
+    /// Watch the exercise files even if the manual run mode is enabled in the config file
+    #[arg(long, conflicts_with = "manual_run")]
+    no_manual_run: bool,
+    /// Disable all colors and text attributes in the output
+    #[arg(long)]
+    no_color: bool,
+    /// Plain text output for screen readers without colors, emojis or a graphical progress bar
+    #[arg(long)]
+    a11y: bool,
+    /// Hide the prompt of the watch mode until a key is pressed
+    #[arg(long)]
+    focus: bool,
+    /// Only show one line with the current exercise, whether it is done and the progress.
+    /// Meant for status bars and small panes
+    #[arg(long)]
+    status_line: bool,
+    /// Redraw only the changed lines instead of clearing the whole screen when possible.
+    /// Meant for slow remote connections
+    #[arg(long, conflicts_with = "status_line")]
+    low_bandwidth: bool,
+    /// Allow peeking at the first lines of the solution before the exercise is done
+    #[arg(long)]
+    peek_while_pending: bool,
+    /// Send progress events of the watch mode as newline-delimited JSON to this Unix socket
+    /// or named pipe (e.g. for editor integrations)
+    #[arg(long)]
+    events: Option<PathBuf>,
+    /// Show a desktop notification when checking an exercise took a while in the watch mode.
+    /// Requires `notify-send` on Linux. Not supported on Windows
+    #[arg(long)]
+    notify: bool,
+    /// Also check the current exercise every this number of seconds if its file changed.
+    /// Catches changes that the file watcher missed (e.g. by external tools)
+    #[arg(long, value_name = "SECONDS")]
+    interval: Option<NonZeroU64>,
+    /// Don't animate a spinner while checking an exercise (e.g. for dumb terminals)
+    #[arg(long)]
+    no_spinner: bool,
+    /// The language of the watch mode, e.g. `en`.
+    /// Defaults to the language of the locale (`LANG`). Falls back to English
+    #[arg(long)]
+    lang: Option<String>,
+    /// Quit the watch mode without a confirmation if there are pending exercises
+    #[arg(long)]
+    no_confirm: bool,
+    /// Hide the hints in the watch mode to challenge yourself. Not even their existence is shown
+    #[arg(long)]
+    no_hints: bool,
+    /// Don't show solutions after finishing exercises (e.g. in classrooms)
+    #[arg(long, visible_alias = "offline")]
+    no_solutions: bool,
+    /// Automatically move on to the next pending exercise shortly after the current one is done
+    #[arg(long)]
+    auto_advance: bool,
+    /// Extra flags passed to `rustc` when compiling exercises, e.g. `-C opt-level=3`.
+    /// Overrides `rustflags` in the config file
+    #[arg(long, allow_hyphen_values = true)]
+    rustflags: Option<String>,
+    /// Check at most this number of exercises in parallel (e.g. in `check-all`).
+    /// Defaults to the number of available CPU cores
+    #[arg(long, short)]
+    jobs: Option<NonZeroUsize>,
+    /// Build and run the exercises with optimizations (the release profile).
+    /// Building takes longer, but some exercises behave differently when optimized
+    #[arg(long)]
+    release: bool,
+    /// Start at the first pending exercise instead of resuming at the exercise that was current
+    /// when quitting the last time
+    #[arg(long)]
+    start_at_first_pending: bool,
+    /// Lock exercises until their prerequisites in `info.toml` are done. By default, the
+    /// exercises can be done in any order
+    #[arg(long)]
+    enforce_prerequisites: bool,
+    /// Check all exercises on startup to correct their status after the files were changed
+    /// while Rustlings wasn't running. Otherwise, only the changed done exercises are marked
+    /// as pending
+    #[arg(long)]
+    recheck: bool,
+    /// Only load the exercises with names containing this pattern.
+    /// `*` matches any sequence of characters, e.g. `iterators*`
+    #[arg(long)]
+    filter: Option<String>,
+    /// Only load the exercises with this difficulty and those without a difficulty
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+    /// Shuffle the order of the exercise topics using this seed. The order inside a topic is kept
+    /// because its exercises build on each other
+    #[arg(long)]
+    seed: Option<u64>,
+    /// The Rustlings directory with the exercises. Defaults to the current directory.
+    /// All files like `info.toml`, the solutions and the state file are relative to it
+    #[arg(long, visible_alias = "exercises-dir")]
+    path: Option<PathBuf>,
+    /// Print the path of the state file which stores the progress and whether it is readable
+    #[arg(long)]
+    state_path: bool,
+    /// Enable hidden commands for developing exercises.
+    /// `F` in the watch mode marks all exercises as done in the state file
+    #[arg(long, hide = true)]
+    dev: bool,
 }
 
 #[derive(Subcommand)]
@@ -45,19 +170,68 @@ enum Subcommands {
     Run {
         /// The name of the exercise
         name: Option<String>,
+        /// Print the result as a JSON object instead of the human-readable output
+        #[arg(long)]
+        json: bool,
     },
     /// Check all the exercises, marking them as done or pending accordingly.
-    CheckAll,
-    /// Reset a single exercise
-    Reset {
+    CheckAll {
+        /// Print the results as a JSON array instead of the human-readable output
+        #[arg(long)]
+        json: bool,
+        /// Only print a summary without updating the progress.
+        /// Exits with a nonzero status if any exercise fails. Meant for CI
+        #[arg(long, conflicts_with = "json")]
+        dry_run: bool,
+    },
+    /// Check the source code read from stdin as the given exercise without changing its file
+    /// or the progress (e.g. for course tooling)
+    CheckStdin {
         /// The name of the exercise
         name: String,
     },
+    /// Print the exercises with their status without the interactive list (e.g. for piping)
+    List {
+        /// Only print the pending exercises
+        #[arg(long)]
+        pending_only: bool,
+    },
+    /// Reset a single exercise, a range of exercises or all of them
+    Reset {
+        /// The name of the exercise. The first exercise of the range if `--to` is used
+        #[arg(required_unless_present = "all")]
+        name: Option<String>,
+        /// Reset all exercises from `NAME` to this exercise (inclusive)
+        #[arg(long, requires = "name")]
+        to: Option<String>,
+        /// Reset all exercises
+        #[arg(long, conflicts_with_all = ["name", "to"])]
+        all: bool,
+    },
+    /// Clear the progress of all exercises to start over
+    ClearProgress {
+        /// Also reset all exercise files to undo ALL your changes to them
+        #[arg(long)]
+        reset_files: bool,
+    },
     /// Show a hint. Shows the hint of the next pending exercise if the exercise name is not specified
     Hint {
         /// The name of the exercise
         name: Option<String>,
     },
+    /// Print the info of all exercises as JSON without changing the progress (e.g. for tooling)
+    Info,
+    /// Run all exercises one after another and print their times sorted by the slowest first
+    /// without changing the progress (e.g. to find exercises that are slow to build)
+    Bench,
+    /// Export the progress as a report (e.g. for study groups)
+    Report {
+        /// The path of the report file. `-` prints the report to stdout
+        path: PathBuf,
+        /// The format of the report
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+    },
     /// Commands for developing (third-party) Rustlings exercises
     #[command(subcommand)]
     Dev(DevCommands),
@@ -80,6 +254,32 @@ fn main() -> Result<ExitCode> {
         return Ok(ExitCode::SUCCESS);
     }
 
+    if let Some(path) = &args.path {
+        // Third-party exercises have an `info.toml` file. The official ones are embedded.
+        if !path.join("info.toml").is_file() && !path.join("exercises").is_dir() {
+            bail!(
+                "The directory `{}` doesn't contain an `info.toml` file or an `exercises` directory",
+                path.display(),
+            );
+        }
+
+        // All paths are relative to the current directory.
+        env::set_current_dir(path)
+            .with_context(|| format!("Failed to change into the directory `{}`", path.display()))?;
+    }
+
+    if args.state_path {
+        let path = app_state::state_file_path()?;
+        println!("{}", path.display());
+        match File::open(&path) {
+            Ok(_) => println!("The file exists and is readable"),
+            Err(e) if e.kind() == ErrorKind::NotFound => println!("The file doesn't exist yet"),
+            Err(e) => println!("The file isn't readable: {e}"),
+        }
+
+        return Ok(ExitCode::SUCCESS);
+    }
+
     if !Path::new("exercises").is_dir() {
         println!("{PRE_INIT_MSG}");
         return Ok(ExitCode::FAILURE);
@@ -87,17 +287,70 @@ fn main() -> Result<ExitCode> {
 
     let info_file = InfoFile::parse()?;
 
-    if info_file.format_version > CURRENT_FORMAT_VERSION {
-        bail!(FORMAT_VERSION_HIGHER_ERR);
+    info_file.check_format_version()?;
+
+    if let Some(Subcommands::Info) = args.command {
+        info_file.print_json()?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let config = Config::read()?;
+    let theme = Theme::build(
+        &config.theme,
+        Completion::build(&config.completion),
+        args.no_color,
+        args.a11y,
+    )?;
+    let keys = Keys::build(&config.keys)?;
+    let cmd_runner = CmdRunner::build()?
+        .with_extra_rustflags(args.rustflags.or(config.rustflags))
+        .with_color(theme.styled())
+        .with_jobs(args.jobs)
+        .with_release(args.release)
+        .with_timeout(match config.timeout {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(DEFAULT_TIMEOUT),
+        })
+        .with_max_output_len(match config.max_output_len {
+            Some(0) => None,
+            Some(max_output_len) => Some(max_output_len),
+            None => Some(DEFAULT_MAX_OUTPUT_LEN),
+        });
+
+    // Handled before loading the state to never change it.
+    if let Some(Subcommands::CheckStdin { name }) = &args.command {
+        let exercise_info = info_file
+            .exercises
+            .iter()
+            .find(|exercise_info| exercise_info.name == *name)
+            .with_context(|| format!("No exercise found for '{name}'!"))?;
+        return run::check_stdin(exercise_info, cmd_runner, &theme);
     }
 
-    let (mut app_state, state_file_status) = AppState::new(
+    let (mut app_state, state_file_status, warnings) = AppState::new(
         info_file.exercises,
         info_file.final_message.unwrap_or_default(),
+        cmd_runner,
+        theme,
+        ExerciseFilter {
+            name: args.filter,
+            difficulty: args.difficulty,
+        },
+        args.no_solutions,
+        args.seed,
     )?;
 
+    for warning in warnings {
+        eprintln!("Warning: {warning}");
+    }
+
     // Show the welcome message if the state file doesn't exist yet.
-    if let Some(welcome_message) = info_file.welcome_message {
+    // Don't block on input if not running in a terminal (e.g. in CI).
+    if let Some(welcome_message) = info_file
+        .welcome_message
+        .filter(|_| io::stdin().is_terminal() && io::stdout().is_terminal())
+    {
         match state_file_status {
             StateFileStatus::NotRead => {
                 let mut stdout = io::stdout().lock();
@@ -114,36 +367,118 @@ fn main() -> Result<ExitCode> {
         }
     }
 
+    if args.recheck {
+        let mut stdout = io::stdout().lock();
+        if let Some(first_pending_exercise_ind) = app_state.check_all_exercises(&mut stdout)? {
+            if app_state.current_exercise().done {
+                app_state.set_current_exercise_ind(first_pending_exercise_ind)?;
+            }
+        }
+        stdout.write_all(b"\n")?;
+    } else if matches!(args.command, None | Some(Subcommands::Run { .. })) {
+        // Other commands don't change the progress.
+        let n_changed = app_state.mark_changed_exercises_pending()?;
+        if n_changed > 0 {
+            // stderr to not break the JSON output of some commands.
+            if n_changed == 1 {
+                eprintln!("1 previously done exercise changed and will be checked again");
+            } else {
+                eprintln!(
+                    "{n_changed} previously done exercises changed and will be checked again"
+                );
+            }
+
+            // The watch mode clears the terminal.
+            if args.command.is_none() && io::stdin().is_terminal() {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(b"Press ENTER to continue ")?;
+                press_enter_prompt(&mut stdout)?;
+            }
+        }
+    }
+
+    // After correcting the status of the exercises.
+    if args.enforce_prerequisites {
+        app_state.enforce_prerequisites()?;
+    }
+    if args.start_at_first_pending {
+        app_state.set_current_exercise_to_first_pending()?;
+    }
+
     match args.command {
         None => {
             if !io::stdout().is_terminal() {
                 bail!("Unsupported or missing terminal/TTY");
             }
 
-            let notify_exercise_names = if args.manual_run {
+            let manual_run = if args.manual_run {
+                true
+            } else if args.no_manual_run {
+                false
+            } else {
+                config.manual_run.unwrap_or(false)
+            };
+
+            let events = args
+                .events
+                .as_deref()
+                .map(EventStream::connect)
+                .transpose()?;
+
+            let notify_exercise_names = if manual_run {
                 None
             } else {
-                // For the notify event handler thread.
-                // Leaking is not a problem because the slice lives until the end of the program.
-                Some(
-                    &*app_state
-                        .exercises()
-                        .iter()
-                        .map(|exercise| exercise.name.as_bytes())
-                        .collect::<Vec<_>>()
-                        .leak(),
-                )
+                Some(watch::notify_exercise_names(&app_state))
             };
 
-            watch::watch(&mut app_state, notify_exercise_names)?;
+            watch::watch(
+                &mut app_state,
+                notify_exercise_names,
+                WatchOptions {
+                    confirm_quit: !args.no_confirm,
+                    auto_advance: args.auto_advance,
+                    keys,
+                    focus_mode: args.focus,
+                    notify: args.notify,
+                    // The animation can't be followed by screen readers.
+                    spinner: !args.no_spinner && !args.a11y,
+                    dev: args.dev,
+                    messages: Messages::select(args.lang.as_deref()),
+                    // `0` would make the debouncer spin.
+                    debounce: config.debounce_ms.map_or(DEFAULT_DEBOUNCE_DURATION, |ms| {
+                        Duration::from_millis(ms.max(1))
+                    }),
+                    status_line: args.status_line,
+                    low_bandwidth: args.low_bandwidth,
+                    peek_lines: config.peek_lines.unwrap_or(DEFAULT_PEEK_LINES),
+                    peek_while_pending: args.peek_while_pending,
+                    events,
+                    error_notes: config.error_notes.unwrap_or(true),
+                    no_hints: args.no_hints,
+                    interval: args.interval.map(|secs| Duration::from_secs(secs.get())),
+                    two_columns: config.two_columns.unwrap_or(false),
+                    rust_analyzer: config.rust_analyzer.unwrap_or(false),
+                },
+            )?;
         }
-        Some(Subcommands::Run { name }) => {
+        Some(Subcommands::Run { name, json }) => {
             if let Some(name) = name {
                 app_state.set_current_exercise_by_name(&name)?;
             }
+
+            if json {
+                return run::run_json(&mut app_state);
+            }
+
             return run::run(&mut app_state);
         }
-        Some(Subcommands::CheckAll) => {
+        Some(Subcommands::CheckAll { json: true, .. }) => {
+            return run::check_all_json(&mut app_state);
+        }
+        Some(Subcommands::CheckAll { dry_run: true, .. }) => {
+            return run::check_all_dry_run(&app_state);
+        }
+        Some(Subcommands::CheckAll { .. }) => {
             let mut stdout = io::stdout().lock();
             if let Some(first_pending_exercise_ind) = app_state.check_all_exercises(&mut stdout)? {
                 if app_state.current_exercise().done {
@@ -163,7 +498,7 @@ fn main() -> Result<ExitCode> {
                 }
                 app_state
                     .current_exercise()
-                    .terminal_file_link(&mut stdout)?;
+                    .terminal_file_link(&mut stdout, app_state.theme())?;
                 stdout.write_all(b"\n")?;
 
                 return Ok(ExitCode::FAILURE);
@@ -171,19 +506,92 @@ fn main() -> Result<ExitCode> {
                 app_state.render_final_message(&mut stdout)?;
             }
         }
-        Some(Subcommands::Reset { name }) => {
+        Some(Subcommands::Bench) => return run::bench(&app_state),
+        Some(Subcommands::List { pending_only }) => list::print(&app_state, pending_only)?,
+        Some(Subcommands::Report { path, format }) => {
+            report::export(&app_state, &path, format)?;
+        }
+        Some(Subcommands::Reset {
+            name: Some(name),
+            to: None,
+            ..
+        }) => {
             app_state.set_current_exercise_by_name(&name)?;
             let exercise_path = app_state.reset_current_exercise()?;
             println!("The exercise {exercise_path} has been reset");
         }
+        Some(Subcommands::Reset { name, to, .. }) => {
+            let range = match (name, to) {
+                (Some(from), Some(to)) => {
+                    let start = app_state.exercise_ind_by_name(&from)?;
+                    let end = app_state.exercise_ind_by_name(&to)?;
+                    if end < start {
+                        bail!("The exercise `{to}` comes before `{from}`");
+                    }
+                    start..end + 1
+                }
+                // `--all`
+                _ => 0..app_state.exercises().len(),
+            };
+
+            let mut stdout = io::stdout().lock();
+            write!(
+                stdout,
+                "This will overwrite {} exercise files and undo ALL your changes to them!\nType `yes` to continue: ",
+                range.len(),
+            )?;
+            stdout.flush()?;
+
+            let mut answer = String::new();
+            io::stdin()
+                .read_line(&mut answer)
+                .context("Failed to read the user's input")?;
+            if answer.trim() != "yes" {
+                stdout.write_all(b"Nothing was reset\n")?;
+                return Ok(ExitCode::FAILURE);
+            }
+
+            let n_reset = app_state.reset_exercises(range)?;
+            writeln!(stdout, "{n_reset} exercises have been reset")?;
+        }
+        Some(Subcommands::ClearProgress { reset_files }) => {
+            let mut stdout = io::stdout().lock();
+            write!(
+                stdout,
+                "This will irreversibly clear your progress of all {} exercises",
+                app_state.exercises().len(),
+            )?;
+            if reset_files {
+                stdout.write_all(b" and overwrite their files to undo ALL your changes to them")?;
+            }
+            stdout.write_all(b"!\nType `yes` to continue: ")?;
+            stdout.flush()?;
+
+            let mut answer = String::new();
+            io::stdin()
+                .read_line(&mut answer)
+                .context("Failed to read the user's input")?;
+            if answer.trim() != "yes" {
+                stdout.write_all(b"Nothing was cleared\n")?;
+                return Ok(ExitCode::FAILURE);
+            }
+
+            app_state.clear_progress(reset_files)?;
+            stdout.write_all(b"Your progress has been cleared\n")?;
+        }
         Some(Subcommands::Hint { name }) => {
             if let Some(name) = name {
                 app_state.set_current_exercise_by_name(&name)?;
             }
-            println!("{}", app_state.current_exercise().hint);
+            println!("{}", app_state.current_exercise().hints.join("\n\n"));
         }
-        // Handled in an earlier match.
-        Some(Subcommands::Init | Subcommands::Dev(_)) => (),
+        // Handled earlier.
+        Some(
+            Subcommands::Init
+            | Subcommands::Dev(_)
+            | Subcommands::CheckStdin { .. }
+            | Subcommands::Info,
+        ) => (),
     }
 
     Ok(ExitCode::SUCCESS)
@@ -195,11 +603,6 @@ The new method doesn't include cloning the Rustlings' repository.
 Please follow the instructions in `README.md`:
 https://github.com/rust-lang/rustlings#getting-started";
 
-const FORMAT_VERSION_HIGHER_ERR: &str =
-    "The format version specified in the `info.toml` file is higher than the last one supported.
-It is possible that you have an outdated version of Rustlings.
-Try to install the latest Rustlings version first.";
-
 const PRE_INIT_MSG: &str = r"
        Welcome to...
                  _   _ _
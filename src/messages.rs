@@ -0,0 +1,284 @@
+use std::env;
+
+/// The user-facing strings of the watch mode in one language.
+/// The keys of the commands are the same in all languages to match the documentation.
+pub struct Messages {
+    pub checking_exercise: &'static str,
+    pub in_release_mode: &'static str,
+    pub please_wait: &'static str,
+    pub warnings: &'static str,
+    pub warning: &'static str,
+    pub notes: &'static str,
+    pub about_the_errors: &'static str,
+    pub diagnostics: &'static str,
+    pub hint: &'static str,
+    pub exercise_done: &'static str,
+    /// Followed by the number of failed runs.
+    pub fixed_after: &'static str,
+    pub failed_run: &'static str,
+    pub failed_runs: &'static str,
+    pub completed_in: &'static str,
+    /// Followed by the key of the `next` command.
+    pub when_done: &'static str,
+    pub to_move_on: &'static str,
+    pub current_exercise: &'static str,
+    pub hints_used: &'static str,
+    pub core: &'static str,
+    pub challenges: &'static str,
+    pub current_exercise_passes: &'static str,
+    pub current_exercise_fails: &'static str,
+    pub file_missing: &'static str,
+    /// Followed by the key of the `reset` command.
+    pub reset_to_restore: &'static str,
+    pub next: &'static str,
+    pub prev: &'static str,
+    pub back: &'static str,
+    pub copy_solution: &'static str,
+    pub peek: &'static str,
+    pub hide_peek: &'static str,
+    pub solution_peek: &'static str,
+    pub no_solution: &'static str,
+    pub diff: &'static str,
+    pub hide_diff: &'static str,
+    pub skip: &'static str,
+    pub run: &'static str,
+    pub hint_cmd: &'static str,
+    pub next_hint: &'static str,
+    pub hint_previously_viewed: &'static str,
+    pub hide_hints: &'static str,
+    pub show_hints: &'static str,
+    pub hint_pager: &'static str,
+    pub bookmark: &'static str,
+    pub remove_bookmark: &'static str,
+    pub groups: &'static str,
+    pub hide_groups: &'static str,
+    pub stats: &'static str,
+    pub note: &'static str,
+    pub report: &'static str,
+    pub wrap: &'static str,
+    pub unwrap: &'static str,
+    pub pause_watcher: &'static str,
+    pub resume_watcher: &'static str,
+    pub watcher_paused: &'static str,
+    pub redraw: &'static str,
+    pub view: &'static str,
+    pub edit: &'static str,
+    pub list: &'static str,
+    pub check: &'static str,
+    pub check_all: &'static str,
+    pub next_failure: &'static str,
+    pub reset: &'static str,
+    pub undo_reset: &'static str,
+    pub reload: &'static str,
+    pub quit: &'static str,
+    /// Followed by the name of the exercise.
+    pub the_exercise: &'static str,
+    pub passed: &'static str,
+    pub failed: &'static str,
+    /// Followed by the exercises that aren't done yet.
+    pub locked_until: &'static str,
+    pub auto_advancing: &'static str,
+    /// Followed by the path of the exercise file.
+    pub reset_undoes: &'static str,
+    pub reset_prompt: &'static str,
+    /// Followed by the number of exercises.
+    pub reset_all_overwrites: &'static str,
+    pub reset_all_undoes: &'static str,
+    pub reset_all_prompt: &'static str,
+    /// Followed by the editor command.
+    pub editor_failed: &'static str,
+    pub editor_not_set: &'static str,
+    pub press_enter: &'static str,
+    pub one_pending: &'static str,
+    /// Followed by the number of pending exercises.
+    pub there_are_still: &'static str,
+    pub exercises_pending: &'static str,
+    pub quit_prompt: &'static str,
+    pub stats_heading: &'static str,
+    pub stats_done: &'static str,
+    pub stats_streak: &'static str,
+    pub stats_hints_viewed: &'static str,
+    pub stats_hints_shown: &'static str,
+    pub stats_peeks: &'static str,
+    pub stats_time_spent: &'static str,
+    /// Followed by the number of days.
+    pub stats_last: &'static str,
+    pub stats_days: &'static str,
+    pub not_enough_data: &'static str,
+    pub press_any_key: &'static str,
+    pub note_prompt: &'static str,
+    pub report_prompt: &'static str,
+    /// Followed by the path of the reports file.
+    pub report_saved: &'static str,
+    pub diff_heading: &'static str,
+    pub diff_legend: &'static str,
+    pub solution_path_copied: &'static str,
+    /// Followed by the clipboard commands.
+    pub no_clipboard: &'static str,
+    /// Followed by the path of the solution file.
+    pub solution_path_is: &'static str,
+}
+
+static EN: Messages = Messages {
+    checking_exercise: "Checking the exercise",
+    in_release_mode: "in release mode",
+    please_wait: "Please wait…",
+    warnings: "Warnings",
+    warning: "Warning",
+    notes: "Notes",
+    about_the_errors: "About the errors",
+    diagnostics: "rust-analyzer",
+    hint: "Hint",
+    exercise_done: "Exercise done",
+    fixed_after: "You fixed it after",
+    failed_run: "failed run",
+    failed_runs: "failed runs",
+    completed_in: "Completed in",
+    when_done: "When done experimenting, enter",
+    to_move_on: "to move on to the next exercise",
+    current_exercise: "Current exercise",
+    hints_used: "hints used",
+    core: "Core",
+    challenges: "Challenges",
+    current_exercise_passes: "The current exercise passes",
+    current_exercise_fails: "The current exercise fails",
+    file_missing: "File missing",
+    reset_to_restore: "Reset the exercise to restore it by entering",
+    next: "next",
+    prev: "prev",
+    back: "back",
+    copy_solution: "copy solution",
+    peek: "peek solution",
+    hide_peek: "hide peek",
+    solution_peek: "Solution peek",
+    no_solution: "The exercise doesn't have a solution to peek at",
+    diff: "diff",
+    hide_diff: "hide diff",
+    skip: "skip",
+    run: "run",
+    hint_cmd: "hint",
+    next_hint: "next hint",
+    hint_previously_viewed: "hint (previously viewed)",
+    hide_hints: "hide hints",
+    show_hints: "show hints",
+    hint_pager: "hints in pager",
+    bookmark: "bookmark",
+    remove_bookmark: "remove bookmark",
+    groups: "groups",
+    hide_groups: "hide groups",
+    stats: "stats",
+    note: "note",
+    report: "report problem",
+    wrap: "wrap",
+    unwrap: "unwrap",
+    pause_watcher: "pause watcher",
+    resume_watcher: "resume watcher",
+    watcher_paused: "The file watcher is paused. Saving the exercise doesn't run it",
+    redraw: "redraw",
+    view: "view",
+    edit: "edit",
+    list: "list",
+    check: "check",
+    check_all: "check all",
+    next_failure: "next failure",
+    reset: "reset",
+    undo_reset: "undo reset",
+    reload: "reload",
+    quit: "quit",
+    the_exercise: "The exercise",
+    passed: "passed",
+    failed: "failed",
+    locked_until: "is locked until these exercises are done:",
+    auto_advancing: "Advancing to the next exercise… Press any key to stay",
+    reset_undoes: "Resetting will undo all your changes to the file",
+    reset_prompt: "Reset (y/n, a: all exercises)?",
+    reset_all_overwrites: "Resetting all exercises will overwrite",
+    reset_all_undoes: "exercise files and undo ALL your changes to them!
+They will all be marked as pending.",
+    reset_all_prompt: "Press `R` (uppercase) to confirm or any other key to cancel:",
+    editor_failed: "Failed to run the editor",
+    editor_not_set: "Set the environment variable `EDITOR` to the command that opens your editor.
+For example: `export EDITOR=nano`",
+    press_enter: "Press ENTER to continue",
+    one_pending: "There is still one exercise pending.",
+    there_are_still: "There are still",
+    exercises_pending: "exercises pending.",
+    quit_prompt: "Quit (y/n)?",
+    stats_heading: "Stats",
+    stats_done: "Done",
+    stats_streak: "Streak",
+    stats_hints_viewed: "Hints viewed",
+    stats_hints_shown: "Hints shown",
+    stats_peeks: "Peeks",
+    stats_time_spent: "Time spent",
+    stats_last: "Last",
+    stats_days: "days",
+    not_enough_data: "not enough data",
+    press_any_key: "Press any key to return",
+    note_prompt: "Note (ENTER to save, ESC to cancel, `-` to delete all notes):",
+    report_prompt: "Describe the problem with this exercise (ENTER to save, ESC to cancel):",
+    report_saved: "The report was saved to",
+    diff_heading: "Diff",
+    diff_legend: "(- your exercise file, + the solution)",
+    solution_path_copied: "Copied the solution path to the clipboard",
+    no_clipboard: "Copying to the clipboard failed. Install one of these commands:",
+    solution_path_is: "The solution path is",
+};
+
+/// The names in backticks separated by commas.
+pub fn quoted_list(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Language codes to their messages. Add new languages here.
+const LANGUAGES: &[(&str, &Messages)] = &[("en", &EN)];
+
+// The language code of a locale like `de_DE.UTF-8`.
+fn language_code(locale: &str) -> &str {
+    locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or_default()
+}
+
+impl Messages {
+    /// The messages of the language `lang` if set. Otherwise, the language of the locale
+    /// environment variables is used.
+    /// Falls back to English if the language isn't translated.
+    pub fn select(lang: Option<&str>) -> &'static Self {
+        let locale = match lang {
+            Some(lang) => lang.to_string(),
+            // The usual order of precedence of these variables.
+            None => ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .into_iter()
+                .filter_map(|var| env::var(var).ok())
+                .find(|value| !value.is_empty())
+                .unwrap_or_default(),
+        };
+        let code = language_code(&locale);
+
+        LANGUAGES
+            .iter()
+            .find(|(language, _)| language.eq_ignore_ascii_case(code))
+            .map_or(&EN, |(_, messages)| messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select() {
+        assert_eq!(language_code("de_DE.UTF-8"), "de");
+        assert_eq!(language_code("en"), "en");
+        assert_eq!(language_code("C"), "C");
+
+        assert!(std::ptr::eq(Messages::select(Some("en_US")), &EN));
+        assert!(std::ptr::eq(Messages::select(Some("xx")), &EN));
+    }
+}
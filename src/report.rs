@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{app_state::AppState, exercise::Exercise, term::write_duration};
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+// An exercise in the JSON report.
+#[derive(Serialize)]
+struct JsonExercise<'a> {
+    name: &'a str,
+    path: &'a str,
+    done: bool,
+    time_spent_secs: u64,
+    bookmarked: bool,
+    hint_viewed: bool,
+    hints_shown: u32,
+    notes: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    n_done: u16,
+    n_exercises: usize,
+    exercises: Vec<JsonExercise<'a>>,
+}
+
+// Table cells can't contain pipes or line breaks.
+fn write_markdown_cell(report: &mut Vec<u8>, text: &str) {
+    for c in text.chars() {
+        match c {
+            '|' => report.extend_from_slice(b"\\|"),
+            '\n' | '\r' => report.push(b' '),
+            c => {
+                let mut buf = [0; 4];
+                report.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+        }
+    }
+}
+
+fn markdown_report(app_state: &AppState) -> io::Result<Vec<u8>> {
+    let exercises = app_state.exercises();
+    let mut report = Vec::with_capacity(64 * (exercises.len() + 4));
+
+    writeln!(
+        report,
+        "# Rustlings progress\n\n{}/{} exercises done\n",
+        app_state.n_done(),
+        exercises.len(),
+    )?;
+    report.extend_from_slice(
+        b"| Exercise | Done | Time spent | Hints shown | Bookmarked | Notes |\n| --- | --- | --- | --- | --- | --- |\n",
+    );
+
+    for exercise in exercises {
+        let Exercise {
+            name,
+            done,
+            time_spent,
+            bookmarked,
+            notes,
+            n_hints_shown,
+            ..
+        } = exercise;
+
+        write!(report, "| {name} | {} | ", if *done { "yes" } else { "no" })?;
+        if !time_spent.is_zero() {
+            write_duration(&mut report, *time_spent)?;
+        }
+        write!(
+            report,
+            " | {n_hints_shown} | {} | ",
+            if *bookmarked { "yes" } else { "" },
+        )?;
+        for (ind, note) in notes.iter().enumerate() {
+            if ind > 0 {
+                report.extend_from_slice(b"; ");
+            }
+            write_markdown_cell(&mut report, note);
+        }
+        report.extend_from_slice(b" |\n");
+    }
+
+    Ok(report)
+}
+
+fn json_report(app_state: &AppState) -> Result<Vec<u8>> {
+    let report = JsonReport {
+        n_done: app_state.n_done(),
+        n_exercises: app_state.exercises().len(),
+        exercises: app_state
+            .exercises()
+            .iter()
+            .map(|exercise| JsonExercise {
+                name: exercise.name,
+                path: exercise.path,
+                done: exercise.done,
+                time_spent_secs: exercise.time_spent.as_secs(),
+                bookmarked: exercise.bookmarked,
+                hint_viewed: exercise.hint_viewed,
+                hints_shown: exercise.n_hints_shown,
+                notes: &exercise.notes,
+            })
+            .collect(),
+    };
+
+    let mut buf = serde_json::to_vec_pretty(&report).context("Failed to serialize the report")?;
+    buf.push(b'\n');
+
+    Ok(buf)
+}
+
+/// Write a report of the progress to the file at `path` or to stdout if `path` is `-`.
+pub fn export(app_state: &AppState, path: &Path, format: ReportFormat) -> Result<()> {
+    let report = match format {
+        ReportFormat::Markdown => markdown_report(app_state)?,
+        ReportFormat::Json => json_report(app_state)?,
+    };
+
+    if path == Path::new("-") {
+        return io::stdout()
+            .lock()
+            .write_all(&report)
+            .context("Failed to write the report to stdout");
+    }
+
+    fs::write(path, report)
+        .with_context(|| format!("Failed to write the report to `{}`", path.display()))?;
+    println!("The report has been written to `{}`", path.display());
+
+    Ok(())
+}
@@ -1,22 +1,29 @@
-use anyhow::Result;
-use crossterm::{
-    style::{Color, ResetColor, SetForegroundColor},
-    QueueableCommand,
-};
+use anyhow::{Context, Result};
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use serde::Serialize;
 use std::{
-    io::{self, Write},
-    process::ExitCode,
+    env, fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::{self, ExitCode},
+    time::{Duration, Instant},
 };
 
 use crate::{
     app_state::{AppState, ExercisesProgress},
+    cargo_toml::bins_start_end_ind,
+    cmd::CmdRunner,
     exercise::{solution_link_line, RunnableExercise, OUTPUT_CAPACITY},
+    info_file::ExerciseInfo,
+    theme::Theme,
 };
 
 pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
     let exercise = app_state.current_exercise();
     let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
-    let success = exercise.run_exercise(Some(&mut output), app_state.cmd_runner())?;
+    let success = app_state
+        .cmd_runner()
+        .run_exercise(exercise, Some(&mut output))?;
 
     let mut stdout = io::stdout().lock();
     stdout.write_all(&output)?;
@@ -27,21 +34,25 @@ pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
         stdout.write_all(b"Ran ")?;
         app_state
             .current_exercise()
-            .terminal_file_link(&mut stdout)?;
+            .terminal_file_link(&mut stdout, app_state.theme())?;
         stdout.write_all(b" with errors\n")?;
 
         return Ok(ExitCode::FAILURE);
     }
 
-    stdout.queue(SetForegroundColor(Color::Green))?;
-    stdout.write_all("✓ Successfully ran ".as_bytes())?;
+    let theme = app_state.theme();
+    theme.queue_style(&mut stdout, SetForegroundColor(Color::Green))?;
+    if !theme.a11y() {
+        stdout.write_all("✓ ".as_bytes())?;
+    }
+    stdout.write_all(b"Successfully ran ")?;
     stdout.write_all(exercise.path.as_bytes())?;
-    stdout.queue(ResetColor)?;
+    theme.queue_style(&mut stdout, ResetColor)?;
     stdout.write_all(b"\n")?;
 
     if let Some(solution_path) = app_state.current_solution_path()? {
         stdout.write_all(b"\n")?;
-        solution_link_line(&mut stdout, &solution_path)?;
+        solution_link_line(&mut stdout, &solution_path, app_state.theme())?;
         stdout.write_all(b"\n")?;
     }
 
@@ -50,7 +61,7 @@ pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
             stdout.write_all(b"Next exercise: ")?;
             app_state
                 .current_exercise()
-                .terminal_file_link(&mut stdout)?;
+                .terminal_file_link(&mut stdout, app_state.theme())?;
             stdout.write_all(b"\n")?;
         }
         ExercisesProgress::AllDone => (),
@@ -58,3 +69,253 @@ pub fn run(app_state: &mut AppState) -> Result<ExitCode> {
 
     Ok(ExitCode::SUCCESS)
 }
+
+// The result of running an exercise in the JSON output mode.
+#[derive(Serialize)]
+struct JsonRunResult<'a> {
+    name: &'a str,
+    success: bool,
+    // The outputs of all commands run to check the exercise.
+    stdout: &'a str,
+    stderr: &'a str,
+    duration_ms: u64,
+}
+
+// The result of checking an exercise in the JSON output mode of checking all exercises.
+#[derive(Serialize)]
+struct JsonCheckResult<'a> {
+    name: &'a str,
+    success: bool,
+    output: &'a str,
+}
+
+/// Check all exercises and print the results as a JSON array in the order of the exercises.
+pub fn check_all_json(app_state: &mut AppState) -> Result<ExitCode> {
+    let results = app_state.check_all_exercises_with(true, |_| Ok(()))?;
+
+    let outputs = results
+        .iter()
+        .map(|result| String::from_utf8_lossy(result.output.as_deref().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    let json_results = results
+        .iter()
+        .zip(&outputs)
+        .map(|(result, output)| JsonCheckResult {
+            name: result.name,
+            success: result.success,
+            output,
+        })
+        .collect::<Vec<_>>();
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &json_results)
+        .context("Failed to serialize the check results")?;
+    stdout.write_all(b"\n")?;
+
+    if results.iter().all(|result| result.success) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Check all exercises without changing their status and print a summary for CI.
+pub fn check_all_dry_run(app_state: &AppState) -> Result<ExitCode> {
+    let results = app_state.run_all_exercises(false, |_| Ok(()))?;
+    let failures = results
+        .iter()
+        .filter(|result| !result.success)
+        .collect::<Vec<_>>();
+
+    let mut stdout = io::stdout().lock();
+    writeln!(
+        stdout,
+        "{} passed, {} failed",
+        results.len() - failures.len(),
+        failures.len(),
+    )?;
+
+    if failures.is_empty() {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    stdout.write_all(b"\nFailed exercises:\n")?;
+    for failure in failures {
+        writeln!(stdout, "  {}", failure.name)?;
+    }
+
+    Ok(ExitCode::FAILURE)
+}
+
+/// Run all exercises one after another and print a table of their times sorted by the slowest
+/// first. Running them sequentially keeps the times comparable. The progress isn't changed.
+pub fn bench(app_state: &AppState) -> Result<ExitCode> {
+    let exercises = app_state.exercises();
+    let mut times = Vec::with_capacity(exercises.len());
+
+    // stderr to only have the table in stdout.
+    let mut stderr = io::stderr().lock();
+    for (exercise_ind, exercise) in exercises.iter().enumerate() {
+        writeln!(
+            stderr,
+            "[{}/{}] {}",
+            exercise_ind + 1,
+            exercises.len(),
+            exercise.name,
+        )?;
+
+        let start = Instant::now();
+        let success = app_state.cmd_runner().run_exercise(exercise, None)?;
+        times.push((exercise.name, start.elapsed(), success));
+    }
+    drop(stderr);
+
+    let total = times
+        .iter()
+        .map(|(_, duration, _)| *duration)
+        .sum::<Duration>();
+    // Stable to keep the order of the exercises with the same time.
+    times.sort_by(|(_, a, _), (_, b, _)| b.cmp(a));
+
+    let name_width = times
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or_default()
+        .max("Exercise".len());
+
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{:<name_width$}  {:>9}  Result", "Exercise", "Time")?;
+    for (name, duration, success) in &times {
+        writeln!(
+            stdout,
+            "{name:<name_width$}  {:>8.2}s  {}",
+            duration.as_secs_f64(),
+            if *success { "passes" } else { "fails" },
+        )?;
+    }
+    writeln!(
+        stdout,
+        "{:<name_width$}  {:>8.2}s",
+        "Total",
+        total.as_secs_f64(),
+    )?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Run the current exercise and print the result as a JSON object.
+/// The current exercise isn't changed, even if it is done.
+pub fn run_json(app_state: &mut AppState) -> Result<ExitCode> {
+    let exercise = app_state.current_exercise();
+    let mut stdout = Vec::with_capacity(OUTPUT_CAPACITY);
+    let mut stderr = Vec::with_capacity(OUTPUT_CAPACITY);
+
+    let start = Instant::now();
+    let success =
+        app_state
+            .cmd_runner()
+            .run_exercise_separated(exercise, &mut stdout, &mut stderr)?;
+    let duration_ms = start.elapsed().as_millis().try_into().unwrap_or(u64::MAX);
+
+    let result = JsonRunResult {
+        name: exercise.name,
+        success,
+        stdout: &String::from_utf8_lossy(&stdout),
+        stderr: &String::from_utf8_lossy(&stderr),
+        duration_ms,
+    };
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &result).context("Failed to serialize the run result")?;
+    stdout.write_all(b"\n")?;
+
+    app_state.set_status_and_write(app_state.current_exercise_ind(), success)?;
+
+    if success {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+// Write the source to a temporary Cargo project with the exercise as its only binary.
+// Returns the path of its `Cargo.toml` file.
+fn write_temp_project(dir: &Path, exercise_name: &str, source: &[u8]) -> Result<String> {
+    // A hack to make `cargo run` work when developing Rustlings.
+    let cargo_toml_path = if cfg!(debug_assertions) {
+        "dev/Cargo.toml"
+    } else {
+        "Cargo.toml"
+    };
+    let cargo_toml = fs::read_to_string(cargo_toml_path)
+        .with_context(|| format!("Failed to read the file `{cargo_toml_path}`"))?;
+    // Keep everything else like dependencies and lints.
+    let (bins_start_ind, bins_end_ind) = bins_start_end_ind(&cargo_toml)?;
+    let temp_cargo_toml = format!(
+        "{}\n  {{ name = \"{exercise_name}\", path = \"exercise.rs\" }},\n{}",
+        &cargo_toml[..bins_start_ind],
+        &cargo_toml[bins_end_ind..],
+    );
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create the directory `{}`", dir.display()))?;
+    fs::write(dir.join("exercise.rs"), source)
+        .context("Failed to write the source to the temporary exercise file")?;
+    let manifest_path = dir.join("Cargo.toml");
+    fs::write(&manifest_path, temp_cargo_toml)
+        .context("Failed to write the temporary `Cargo.toml` file")?;
+
+    Ok(manifest_path.to_string_lossy().into_owned())
+}
+
+/// Check the source code read from stdin as the exercise `exercise_info` and print the result.
+/// Neither the exercise file nor the progress is changed. The source is checked in a temporary
+/// copy of the project.
+pub fn check_stdin(
+    exercise_info: &ExerciseInfo,
+    cmd_runner: CmdRunner,
+    theme: &Theme,
+) -> Result<ExitCode> {
+    let mut source = Vec::new();
+    io::stdin()
+        .read_to_end(&mut source)
+        .context("Failed to read the source code from stdin")?;
+
+    let dir = env::temp_dir().join(format!("rustlings-check-stdin-{}", process::id()));
+    let res = write_temp_project(&dir, &exercise_info.name, &source).and_then(|manifest_path| {
+        let cmd_runner = cmd_runner.with_manifest(manifest_path.into(), dir.join("target"));
+        let mut output = Vec::with_capacity(OUTPUT_CAPACITY);
+        let success = exercise_info.run_exercise(Some(&mut output), &cmd_runner)?;
+        Ok((success, output))
+    });
+    // Best effort. The temporary directory is cleaned up by the OS eventually.
+    let _ = fs::remove_dir_all(&dir);
+    let (success, output) = res?;
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(&output)?;
+
+    if !success {
+        writeln!(
+            stdout,
+            "The source code doesn't pass the checks of the exercise `{}`",
+            exercise_info.name,
+        )?;
+        return Ok(ExitCode::FAILURE);
+    }
+
+    theme.queue_style(&mut stdout, SetForegroundColor(Color::Green))?;
+    if !theme.a11y() {
+        stdout.write_all("✓ ".as_bytes())?;
+    }
+    write!(
+        stdout,
+        "The source code passes the checks of the exercise `{}`",
+        exercise_info.name,
+    )?;
+    theme.queue_style(&mut stdout, ResetColor)?;
+    stdout.write_all(b"\n")?;
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,161 @@
+use std::{
+    fs,
+    io::Read,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Analyzing the exercises for the first time can take a while.
+const ANALYSIS_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A diagnostic of rust-analyzer in the exercise file.
+pub struct Diagnostic {
+    pub severity: &'static str,
+    /// 1-based like in the compiler output.
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+    /// The line of the exercise file that the diagnostic starts at.
+    pub source_line: String,
+}
+
+// Run `rust-analyzer diagnostics` and return its stdout.
+// `None` if it isn't installed or takes too long. Its exit status is ignored because it fails
+// if any error is found.
+fn analyze() -> Option<String> {
+    let mut child = Command::new("rust-analyzer")
+        .arg("diagnostics")
+        .arg(".")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Read while waiting. Otherwise, a full pipe would block the process.
+    let mut child_stdout = child.stdout.take()?;
+    let reader = thread::Builder::new()
+        .spawn(move || {
+            let mut output = String::new();
+            child_stdout
+                .read_to_string(&mut output)
+                .ok()
+                .map(|_| output)
+        })
+        .ok()?;
+
+    let start = Instant::now();
+    while child.try_wait().ok()?.is_none() {
+        if start.elapsed() >= ANALYSIS_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    reader.join().ok()?
+}
+
+// Parse a line of the output like
+// `Error RustcHardError("E0308") from LineCol { line: 2, col: 4 } to LineCol { line: 2, col: 9 }: expected i32, found &str`.
+// The lines and columns in the output are 0-based.
+fn parse_diagnostic(line: &str) -> Option<(&'static str, usize, usize, &str)> {
+    let (severity, rest) = line.split_once(' ')?;
+    let severity = match severity {
+        "Error" => "error",
+        "Warning" => "warning",
+        "WeakWarning" => "hint",
+        _ => return None,
+    };
+
+    let (_, rest) = rest.split_once(" from LineCol { line: ")?;
+    let (line_ind, rest) = rest.split_once(", col: ")?;
+    let (col_ind, rest) = rest.split_once(" }")?;
+    let (_, message) = rest.split_once(" }: ")?;
+
+    Some((
+        severity,
+        line_ind.parse::<usize>().ok()? + 1,
+        col_ind.parse::<usize>().ok()? + 1,
+        message,
+    ))
+}
+
+// Only keep the diagnostics of the exercise file. Each file starts with a line like
+// `processing crate: exercises, module: /path/to/exercises/variables1.rs`.
+fn parse(output: &str, exercise_path: &str, source: &str) -> Vec<Diagnostic> {
+    let source_lines = source.lines().collect::<Vec<_>>();
+    let mut in_exercise = false;
+    let mut diagnostics = Vec::new();
+
+    for line in output.lines() {
+        if let Some((_, module)) = line
+            .strip_prefix("processing crate: ")
+            .and_then(|line| line.split_once(", module: "))
+        {
+            in_exercise = Path::new(module).ends_with(exercise_path);
+            continue;
+        }
+
+        if !in_exercise {
+            continue;
+        }
+
+        if let Some((severity, line, col, message)) = parse_diagnostic(line) {
+            diagnostics.push(Diagnostic {
+                severity,
+                line,
+                col,
+                message: message.to_string(),
+                source_line: source_lines
+                    .get(line - 1)
+                    .map(|source_line| source_line.trim().to_string())
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// The diagnostics of rust-analyzer for the exercise file.
+/// Empty if rust-analyzer isn't available which leaves only the compiler output.
+pub fn diagnostics(exercise_path: &str) -> Vec<Diagnostic> {
+    let Some(output) = analyze() else {
+        return Vec::new();
+    };
+    let source = fs::read_to_string(exercise_path).unwrap_or_default();
+
+    parse(&output, exercise_path, &source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_output() {
+        let output = r#"processing crate: exercises, module: /home/user/rustlings/exercises/other.rs
+Error RustcHardError("E0425") from LineCol { line: 0, col: 0 } to LineCol { line: 0, col: 1 }: unresolved name
+processing crate: exercises, module: /home/user/rustlings/exercises/variables1.rs
+Error RustcHardError("E0308") from LineCol { line: 1, col: 17 } to LineCol { line: 1, col: 20 }: expected i32, found &str
+Allow Lint("dead_code") from LineCol { line: 0, col: 0 } to LineCol { line: 0, col: 1 }: ignored
+"#;
+        let source = "fn main() {\n    let x: i32 = \"a\";\n}\n";
+
+        let diagnostics = parse(output, "exercises/variables1.rs", source);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, "error");
+        assert_eq!((diagnostic.line, diagnostic.col), (2, 18));
+        assert_eq!(diagnostic.message, "expected i32, found &str");
+        assert_eq!(diagnostic.source_line, "let x: i32 = \"a\";");
+
+        assert!(parse("", "exercises/variables1.rs", source).is_empty());
+    }
+}
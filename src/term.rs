@@ -1,25 +1,95 @@
 use crossterm::{
     cursor::MoveTo,
     style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor},
-    terminal::{Clear, ClearType},
+    terminal::{self, Clear, ClearType},
     Command, QueueableCommand,
 };
 use std::{
     fmt, fs,
     io::{self, BufRead, StdoutLock, Write},
+    sync::{Mutex, PoisonError},
+    thread,
+    time::Duration,
 };
 
-use crate::app_state::CheckProgress;
+use crate::{app_state::CheckProgress, theme::Theme};
 
-pub struct MaxLenWriter<'a, 'lock> {
-    pub stdout: &'a mut StdoutLock<'lock>,
+const SPINNER_FRAMES: &[u8] = b"|/-\\";
+// Used if the terminal size can't be determined (e.g. in a pipe or CI).
+const DEFAULT_TERM_WIDTH: u16 = 80;
+const DEFAULT_TERM_HEIGHT: u16 = 24;
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+struct SpinnerState {
+    // Identifies the thread of the current spinner. Older threads exit.
+    id: u64,
+    shown: bool,
+}
+
+// Locked while drawing a frame to never draw after stopping the spinner.
+static SPINNER: Mutex<SpinnerState> = Mutex::new(SpinnerState {
+    id: 0,
+    shown: false,
+});
+
+/// Show an animated spinner at the cursor position until the guard is dropped or
+/// `stop_spinner` is called. The cursor should be at the start of an empty line.
+/// The spinner is drawn on stderr because the caller usually holds the stdout lock.
+pub fn start_spinner() -> io::Result<SpinnerGuard> {
+    let id = {
+        let mut spinner = SPINNER.lock().unwrap_or_else(PoisonError::into_inner);
+        spinner.id += 1;
+        spinner.shown = true;
+        spinner.id
+    };
+
+    thread::Builder::new().spawn(move || {
+        for &frame in SPINNER_FRAMES.iter().cycle() {
+            thread::sleep(SPINNER_INTERVAL);
+
+            let spinner = SPINNER.lock().unwrap_or_else(PoisonError::into_inner);
+            if spinner.id != id || !spinner.shown {
+                break;
+            }
+
+            // Move back to the start to overwrite the frame with the next one.
+            let _ = io::stderr().write_all(&[frame, b'\x08']);
+        }
+    })?;
+
+    Ok(SpinnerGuard(()))
+}
+
+/// Stop the spinner if one is shown and clear its last frame.
+/// Has to be called before writing anything to the terminal while the spinner is shown.
+pub fn stop_spinner() {
+    let mut spinner = SPINNER.lock().unwrap_or_else(PoisonError::into_inner);
+    if spinner.shown {
+        spinner.shown = false;
+        let _ = io::stderr().write_all(b" \x08");
+    }
+}
+
+// Private unit type to force using the constructor function.
+#[must_use = "When the guard is dropped, the spinner is stopped"]
+pub struct SpinnerGuard(());
+
+impl Drop for SpinnerGuard {
+    #[inline]
+    fn drop(&mut self) {
+        stop_spinner();
+    }
+}
+
+pub struct MaxLenWriter<'a, W> {
+    pub stdout: &'a mut W,
     len: usize,
     max_len: usize,
 }
 
-impl<'a, 'lock> MaxLenWriter<'a, 'lock> {
+impl<'a, W: Write> MaxLenWriter<'a, W> {
     #[inline]
-    pub fn new(stdout: &'a mut StdoutLock<'lock>, max_len: usize) -> Self {
+    pub fn new(stdout: &'a mut W, max_len: usize) -> Self {
         Self {
             stdout,
             len: 0,
@@ -34,13 +104,18 @@ impl<'a, 'lock> MaxLenWriter<'a, 'lock> {
     }
 }
 
-pub trait CountedWrite<'lock> {
+pub trait CountedWrite {
+    /// Escape sequences are written to it directly because they don't take space.
+    type Writer: Write;
+
     fn write_ascii(&mut self, ascii: &[u8]) -> io::Result<()>;
     fn write_str(&mut self, unicode: &str) -> io::Result<()>;
-    fn stdout(&mut self) -> &mut StdoutLock<'lock>;
+    fn stdout(&mut self) -> &mut Self::Writer;
 }
 
-impl<'lock> CountedWrite<'lock> for MaxLenWriter<'_, 'lock> {
+impl<W: Write> CountedWrite for MaxLenWriter<'_, W> {
+    type Writer = W;
+
     fn write_ascii(&mut self, ascii: &[u8]) -> io::Result<()> {
         let n = ascii.len().min(self.max_len.saturating_sub(self.len));
         if n > 0 {
@@ -65,12 +140,34 @@ impl<'lock> CountedWrite<'lock> for MaxLenWriter<'_, 'lock> {
     }
 
     #[inline]
-    fn stdout(&mut self) -> &mut StdoutLock<'lock> {
+    fn stdout(&mut self) -> &mut W {
         self.stdout
     }
 }
 
-impl<'a> CountedWrite<'a> for StdoutLock<'a> {
+impl CountedWrite for StdoutLock<'_> {
+    type Writer = Self;
+
+    #[inline]
+    fn write_ascii(&mut self, ascii: &[u8]) -> io::Result<()> {
+        self.write_all(ascii)
+    }
+
+    #[inline]
+    fn write_str(&mut self, unicode: &str) -> io::Result<()> {
+        self.write_all(unicode.as_bytes())
+    }
+
+    #[inline]
+    fn stdout(&mut self) -> &mut Self {
+        self
+    }
+}
+
+// For rendering a frame before writing it at once.
+impl CountedWrite for Vec<u8> {
+    type Writer = Self;
+
     #[inline]
     fn write_ascii(&mut self, ascii: &[u8]) -> io::Result<()> {
         self.write_all(ascii)
@@ -82,7 +179,7 @@ impl<'a> CountedWrite<'a> for StdoutLock<'a> {
     }
 
     #[inline]
-    fn stdout(&mut self) -> &mut StdoutLock<'a> {
+    fn stdout(&mut self) -> &mut Self {
         self
     }
 }
@@ -90,6 +187,7 @@ impl<'a> CountedWrite<'a> for StdoutLock<'a> {
 pub struct CheckProgressVisualizer<'a, 'lock> {
     stdout: &'a mut StdoutLock<'lock>,
     n_cols: usize,
+    theme: Theme,
 }
 
 impl<'a, 'lock> CheckProgressVisualizer<'a, 'lock> {
@@ -97,30 +195,45 @@ impl<'a, 'lock> CheckProgressVisualizer<'a, 'lock> {
     const DONE_COLOR: Color = Color::Green;
     const PENDING_COLOR: Color = Color::Red;
 
-    pub fn build(stdout: &'a mut StdoutLock<'lock>, term_width: u16) -> io::Result<Self> {
+    pub fn build(
+        stdout: &'a mut StdoutLock<'lock>,
+        term_width: u16,
+        theme: Theme,
+    ) -> io::Result<Self> {
         clear_terminal(stdout)?;
         stdout.write_all("Checking all exercises…\n".as_bytes())?;
 
         // Legend
-        stdout.write_all(b"Color of exercise number: ")?;
-        stdout.queue(SetForegroundColor(Self::CHECKING_COLOR))?;
-        stdout.write_all(b"Checking")?;
-        stdout.queue(ResetColor)?;
-        stdout.write_all(b" - ")?;
-        stdout.queue(SetForegroundColor(Self::DONE_COLOR))?;
-        stdout.write_all(b"Done")?;
-        stdout.queue(ResetColor)?;
-        stdout.write_all(b" - ")?;
-        stdout.queue(SetForegroundColor(Self::PENDING_COLOR))?;
-        stdout.write_all(b"Pending")?;
-        stdout.queue(ResetColor)?;
-        stdout.write_all(b"\n")?;
-
-        // Exercise numbers with up to 3 digits.
+        if theme.styled() {
+            stdout.write_all(b"Color of exercise number: ")?;
+            stdout.queue(SetForegroundColor(Self::CHECKING_COLOR))?;
+            stdout.write_all(b"Checking")?;
+            stdout.queue(ResetColor)?;
+            stdout.write_all(b" - ")?;
+            stdout.queue(SetForegroundColor(Self::DONE_COLOR))?;
+            stdout.write_all(b"Done")?;
+            stdout.queue(ResetColor)?;
+            stdout.write_all(b" - ")?;
+            stdout.queue(SetForegroundColor(Self::PENDING_COLOR))?;
+            stdout.write_all(b"Pending")?;
+            stdout.queue(ResetColor)?;
+            stdout.write_all(b"\n")?;
+        } else {
+            stdout.write_all(
+                "Mark after exercise number: … Checking - ✓ Done - ✗ Pending\n".as_bytes(),
+            )?;
+        }
+
+        // Exercise numbers with up to 3 digits and a mark without colors.
         // +1 because the last column doesn't end with a whitespace.
-        let n_cols = usize::from(term_width + 1) / 4;
+        let col_width = if theme.styled() { 4 } else { 5 };
+        let n_cols = usize::from(term_width + 1) / col_width;
 
-        Ok(Self { stdout, n_cols })
+        Ok(Self {
+            stdout,
+            n_cols,
+            theme,
+        })
     }
 
     pub fn update(&mut self, progresses: &[CheckProgress]) -> io::Result<()> {
@@ -128,22 +241,32 @@ impl<'a, 'lock> CheckProgressVisualizer<'a, 'lock> {
 
         let mut exercise_num = 1;
         for exercise_progress in progresses {
-            match exercise_progress {
-                CheckProgress::None => (),
-                CheckProgress::Checking => {
-                    self.stdout
-                        .queue(SetForegroundColor(Self::CHECKING_COLOR))?;
-                }
-                CheckProgress::Done => {
-                    self.stdout.queue(SetForegroundColor(Self::DONE_COLOR))?;
+            if !self.theme.styled() {
+                let mark = match exercise_progress {
+                    CheckProgress::None => " ",
+                    CheckProgress::Checking => "…",
+                    CheckProgress::Done => "✓",
+                    CheckProgress::Pending => "✗",
+                };
+                write!(self.stdout, "{exercise_num:<3}{mark}")?;
+            } else {
+                match exercise_progress {
+                    CheckProgress::None => (),
+                    CheckProgress::Checking => {
+                        self.stdout
+                            .queue(SetForegroundColor(Self::CHECKING_COLOR))?;
+                    }
+                    CheckProgress::Done => {
+                        self.stdout.queue(SetForegroundColor(Self::DONE_COLOR))?;
+                    }
+                    CheckProgress::Pending => {
+                        self.stdout.queue(SetForegroundColor(Self::PENDING_COLOR))?;
+                    }
                 }
-                CheckProgress::Pending => {
-                    self.stdout.queue(SetForegroundColor(Self::PENDING_COLOR))?;
-                }
-            }
 
-            write!(self.stdout, "{exercise_num:<3}")?;
-            self.stdout.queue(ResetColor)?;
+                write!(self.stdout, "{exercise_num:<3}")?;
+                self.stdout.queue(ResetColor)?;
+            }
 
             if exercise_num != progresses.len() {
                 if exercise_num % self.n_cols == 0 {
@@ -160,34 +283,86 @@ impl<'a, 'lock> CheckProgressVisualizer<'a, 'lock> {
     }
 }
 
-pub fn progress_bar<'a>(
-    writer: &mut impl CountedWrite<'a>,
+/// One block character per value scaled to the maximum value. Zeros are shown as dots.
+/// Screen readers get the plain numbers.
+pub fn sparkline(
+    writer: &mut impl Write,
+    values: &[u16],
+    color: Color,
+    theme: &Theme,
+) -> io::Result<()> {
+    if theme.a11y() {
+        for (ind, value) in values.iter().enumerate() {
+            if ind > 0 {
+                writer.write_all(b" ")?;
+            }
+            write!(writer, "{value}")?;
+        }
+        return Ok(());
+    }
+
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+
+    theme.queue_style(writer, SetForegroundColor(color))?;
+    for &value in values {
+        if value == 0 {
+            writer.write_all("·".as_bytes())?;
+            continue;
+        }
+
+        // Only zeros are shown without a block.
+        let block_ind = (usize::from(value) * BLOCKS.len()).div_ceil(usize::from(max)) - 1;
+        let mut buf = [0; 4];
+        writer.write_all(BLOCKS[block_ind].encode_utf8(&mut buf).as_bytes())?;
+    }
+    theme.queue_style(writer, ResetColor)
+}
+
+pub fn progress_bar(
+    writer: &mut impl CountedWrite,
     progress: u16,
     total: u16,
     term_width: u16,
+    theme: &Theme,
 ) -> io::Result<()> {
     debug_assert!(total <= 999);
     debug_assert!(progress <= total);
 
+    // A bar can't be read out.
+    if theme.a11y() {
+        // Integers are in ASCII.
+        return writer
+            .write_ascii(format!("Progress: {progress} of {total} exercises done").as_bytes());
+    }
+
     const PREFIX: &[u8] = b"Progress: [";
     const PREFIX_WIDTH: u16 = PREFIX.len() as u16;
-    const POSTFIX_WIDTH: u16 = "] xxx/xxx".len() as u16;
+    const POSTFIX_WIDTH: u16 = "] xxx/xxx xxx%".len() as u16;
     const WRAPPER_WIDTH: u16 = PREFIX_WIDTH + POSTFIX_WIDTH;
     const MIN_LINE_WIDTH: u16 = WRAPPER_WIDTH + 4;
 
+    // An empty exercise set counts as no progress.
+    let percentage = (u32::from(progress) * 100)
+        .checked_div(u32::from(total))
+        .unwrap_or(0);
+
     if term_width < MIN_LINE_WIDTH {
         writer.write_ascii(b"Progress: ")?;
         // Integers are in ASCII.
-        return writer.write_ascii(format!("{progress}/{total}").as_bytes());
+        return writer.write_ascii(format!("{progress}/{total} {percentage}%").as_bytes());
     }
 
     let stdout = writer.stdout();
     stdout.write_all(PREFIX)?;
 
     let width = term_width - WRAPPER_WIDTH;
-    let filled = (width * progress) / total;
+    // `u32` because the product can exceed `u16::MAX`.
+    let filled = (u32::from(width) * u32::from(progress))
+        .checked_div(u32::from(total))
+        .unwrap_or(0) as u16;
 
-    stdout.queue(SetForegroundColor(Color::Green))?;
+    theme.queue_style(stdout, SetForegroundColor(theme.progress_done))?;
     for _ in 0..filled {
         stdout.write_all(b"#")?;
     }
@@ -199,15 +374,243 @@ pub fn progress_bar<'a>(
     let width_minus_filled = width - filled;
     if width_minus_filled > 1 {
         let red_part_width = width_minus_filled - 1;
-        stdout.queue(SetForegroundColor(Color::Red))?;
+        theme.queue_style(stdout, SetForegroundColor(theme.progress_pending))?;
         for _ in 0..red_part_width {
             stdout.write_all(b"-")?;
         }
     }
 
-    stdout.queue(SetForegroundColor(Color::Reset))?;
+    theme.queue_style(stdout, SetForegroundColor(Color::Reset))?;
+
+    write!(stdout, "] {progress:>3}/{total} {percentage:>3}%")
+}
+
+/// The width of the terminal. Falls back to a default instead of failing if it can't be
+/// determined.
+pub fn term_width() -> u16 {
+    term_size().0
+}
+
+/// The width and height of the terminal with the same fallback as `term_width`.
+pub fn term_size() -> (u16, u16) {
+    let (width, height) = terminal::size().unwrap_or((0, 0));
+    (
+        if width > 0 { width } else { DEFAULT_TERM_WIDTH },
+        if height > 0 {
+            height
+        } else {
+            DEFAULT_TERM_HEIGHT
+        },
+    )
+}
 
-    write!(stdout, "] {progress:>3}/{total}")
+/// Write a duration in a compact form like `1h4m12s`, `4m12s` or `12s`.
+pub fn write_duration(writer: &mut impl Write, duration: Duration) -> io::Result<()> {
+    let secs = duration.as_secs();
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        write!(writer, "{hours}h{mins}m{secs}s")
+    } else if mins > 0 {
+        write!(writer, "{mins}m{secs}s")
+    } else {
+        write!(writer, "{secs}s")
+    }
+}
+
+/// The length of the ANSI escape sequence at the start of `bytes` which starts with `ESC`.
+pub fn ansi_escape_len(bytes: &[u8]) -> usize {
+    match bytes.get(1) {
+        // Control Sequence Introducer (CSI) like `ESC[1;33m`.
+        // It ends with a byte in the range `@` to `~`.
+        Some(b'[') => bytes
+            .iter()
+            .skip(2)
+            .position(|byte| (b'@'..=b'~').contains(byte))
+            .map_or(bytes.len(), |ind| ind + 3),
+        // Operating System Command (OSC) like a hyperlink.
+        // It ends with `BEL` or `ESC\`.
+        Some(b']') => bytes
+            .windows(2)
+            .skip(2)
+            .position(|window| window[0] == 0x07 || window == b"\x1b\\")
+            .map_or(bytes.len(), |ind| {
+                if bytes[ind + 2] == 0x07 {
+                    ind + 3
+                } else {
+                    ind + 4
+                }
+            }),
+        Some(_) => 2,
+        None => 1,
+    }
+}
+
+/// Wrap the lines of the output at the given width while preserving ANSI escape sequences.
+/// Lines are wrapped at the last space if possible.
+pub fn wrap_output(output: &[u8], width: usize, wrapped: &mut Vec<u8>) {
+    wrapped.clear();
+
+    if width == 0 {
+        wrapped.extend_from_slice(output);
+        return;
+    }
+
+    // The number of visible characters in the current line.
+    let mut col = 0;
+    // The index of the last space of the current line in `wrapped` with its column.
+    let mut last_space = None;
+    let mut ind = 0;
+
+    while let Some(&byte) = output.get(ind) {
+        if byte == 0x1b {
+            let len = ansi_escape_len(&output[ind..]);
+            wrapped.extend_from_slice(&output[ind..ind + len]);
+            ind += len;
+            continue;
+        }
+
+        if byte == b'\n' {
+            wrapped.push(byte);
+            col = 0;
+            last_space = None;
+            ind += 1;
+            continue;
+        }
+
+        if col == width {
+            // Replace the space at the end of the line with the line break.
+            if byte == b' ' {
+                wrapped.push(b'\n');
+                col = 0;
+                last_space = None;
+                ind += 1;
+                continue;
+            }
+
+            if let Some((space_ind, space_col)) = last_space {
+                wrapped[space_ind] = b'\n';
+                col -= space_col + 1;
+            } else {
+                wrapped.push(b'\n');
+                col = 0;
+            }
+
+            last_space = None;
+        }
+
+        if byte == b' ' {
+            last_space = Some((wrapped.len(), col));
+        }
+
+        // The length of the UTF-8 character depending on its first byte.
+        let char_len = match byte.leading_ones() {
+            2 => 2,
+            3 => 3,
+            4 => 4,
+            _ => 1,
+        }
+        .min(output.len() - ind);
+        wrapped.extend_from_slice(&output[ind..ind + char_len]);
+        col += 1;
+        ind += char_len;
+    }
+}
+
+// The number of visible characters of a line without a line break.
+fn line_width(line: &[u8]) -> usize {
+    let mut width = 0;
+    let mut ind = 0;
+
+    while let Some(&byte) = line.get(ind) {
+        if byte == 0x1b {
+            ind += ansi_escape_len(&line[ind..]);
+            continue;
+        }
+
+        // Continuation bytes of UTF-8 characters are skipped.
+        if byte.leading_ones() != 1 {
+            width += 1;
+        }
+        ind += 1;
+    }
+
+    width
+}
+
+/// Lay out two outputs side by side in the given width separated by a vertical line.
+/// The left one gets the bigger column. Both are wrapped at the width of their column.
+pub fn side_by_side(left: &[u8], right: &[u8], width: usize, frame: &mut Vec<u8>) {
+    // ` │ ` between the columns.
+    let columns_width = width.saturating_sub(3);
+    let left_width = columns_width * 3 / 5;
+    let right_width = columns_width - left_width;
+
+    let mut wrapped_left = Vec::new();
+    wrap_output(left, left_width, &mut wrapped_left);
+    let mut wrapped_right = Vec::new();
+    wrap_output(right, right_width, &mut wrapped_right);
+
+    fn lines(wrapped: &[u8]) -> impl Iterator<Item = &[u8]> {
+        let wrapped = wrapped.strip_suffix(b"\n").unwrap_or(wrapped);
+        wrapped.split(|&byte| byte == b'\n')
+    }
+    let mut left_lines = lines(&wrapped_left);
+    let mut right_lines = lines(&wrapped_right);
+
+    loop {
+        let (left_line, right_line) = match (left_lines.next(), right_lines.next()) {
+            (None, None) => break,
+            (left_line, right_line) => (left_line.unwrap_or_default(), right_line),
+        };
+
+        frame.extend_from_slice(left_line);
+        // Styles don't continue into the right column.
+        if left_line.contains(&0x1b) {
+            frame.extend_from_slice(b"\x1b[0m");
+        }
+        frame.resize(
+            frame.len() + left_width.saturating_sub(line_width(left_line)),
+            b' ',
+        );
+        frame.extend_from_slice(" │".as_bytes());
+
+        if let Some(right_line) = right_line {
+            frame.push(b' ');
+            frame.extend_from_slice(right_line);
+            if right_line.contains(&0x1b) {
+                frame.extend_from_slice(b"\x1b[0m");
+            }
+        }
+        frame.push(b'\n');
+    }
+}
+
+/// The number of terminal rows that `output` takes at the given width.
+/// A last line without a line break only counts if it isn't empty.
+pub fn n_rows(output: &[u8], width: usize) -> usize {
+    let mut n_rows: usize = 0;
+    // The number of visible characters in the current line.
+    let mut col: usize = 0;
+    let mut ind = 0;
+
+    while let Some(&byte) = output.get(ind) {
+        if byte == 0x1b {
+            ind += ansi_escape_len(&output[ind..]);
+            continue;
+        }
+
+        if byte == b'\n' {
+            n_rows += col.div_ceil(width).max(1);
+            col = 0;
+        } else if byte.leading_ones() != 1 {
+            // Continuation bytes of UTF-8 characters are skipped.
+            col += 1;
+        }
+        ind += 1;
+    }
+
+    n_rows + col.div_ceil(width)
 }
 
 pub fn clear_terminal(stdout: &mut StdoutLock) -> io::Result<()> {
@@ -241,18 +644,53 @@ pub fn canonicalize(path: &str) -> Option<String> {
         })
 }
 
-pub fn terminal_file_link<'a>(
-    writer: &mut impl CountedWrite<'a>,
+// The `file://` URL of a canonical path. Bytes which aren't allowed in URLs (e.g. spaces) are
+// percent-encoded. Otherwise, terminals would cut the link at them.
+fn file_url(canonical_path: &str) -> String {
+    let mut url = String::with_capacity(canonical_path.len() + 8);
+    url.push_str("file://");
+    // Windows paths like `C:\…` start without a slash.
+    if !canonical_path.starts_with('/') {
+        url.push('/');
+    }
+
+    for &byte in canonical_path.as_bytes() {
+        match byte {
+            b'\\' if cfg!(windows) => url.push('/'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                url.push(char::from(byte));
+            }
+            _ => {
+                use fmt::Write as _;
+                // Writing to a string can't fail.
+                let _ = write!(url, "%{byte:02X}");
+            }
+        }
+    }
+
+    url
+}
+
+pub fn terminal_file_link(
+    writer: &mut impl CountedWrite,
     path: &str,
     canonical_path: &str,
     color: Color,
+    theme: &Theme,
 ) -> io::Result<()> {
+    // Hyperlinks are escape sequences like colors.
+    if !theme.styled() {
+        return writer.write_str(path);
+    }
+
     writer
         .stdout()
         .queue(SetForegroundColor(color))?
         .queue(SetAttribute(Attribute::Underlined))?;
-    writer.stdout().write_all(b"\x1b]8;;file://")?;
-    writer.stdout().write_all(canonical_path.as_bytes())?;
+    writer.stdout().write_all(b"\x1b]8;;")?;
+    writer
+        .stdout()
+        .write_all(file_url(canonical_path).as_bytes())?;
     writer.stdout().write_all(b"\x1b\\")?;
     // Only this part is visible.
     writer.write_str(path)?;
@@ -277,3 +715,60 @@ pub fn write_ansi(output: &mut Vec<u8>, command: impl Command) {
 
     let _ = command.write_ansi(&mut FmtWriter(output));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi_escape() {
+        assert_eq!(ansi_escape_len(b"\x1b[1;33mwarning"), 7);
+        assert_eq!(ansi_escape_len(b"\x1b]8;;file:///x\x1b\\x"), 16);
+        assert_eq!(ansi_escape_len(b"\x1b]8;;\x07x"), 6);
+        assert_eq!(ansi_escape_len(b"\x1b["), 2);
+    }
+
+    #[test]
+    fn url() {
+        assert_eq!(file_url("/a/b.rs"), "file:///a/b.rs");
+        assert_eq!(file_url("/my dir/ä.rs"), "file:///my%20dir/%C3%A4.rs");
+    }
+
+    #[test]
+    fn wrap() {
+        let mut wrapped = Vec::new();
+
+        wrap_output(b"aaa bbb ccc\nddd", 7, &mut wrapped);
+        assert_eq!(wrapped, b"aaa bbb\nccc\nddd");
+
+        wrap_output(b"aaaaaaaaa", 4, &mut wrapped);
+        assert_eq!(wrapped, b"aaaa\naaaa\na");
+
+        wrap_output(b"\x1b[1;31merror\x1b[0m: x", 6, &mut wrapped);
+        assert_eq!(wrapped, b"\x1b[1;31merror\x1b[0m:\nx");
+
+        wrap_output("✓✓✓".as_bytes(), 2, &mut wrapped);
+        assert_eq!(wrapped, "✓✓\n✓".as_bytes());
+    }
+
+    #[test]
+    fn columns() {
+        let mut frame = Vec::new();
+        side_by_side(b"ab\ncd\n", b"x\n", 10, &mut frame);
+        assert_eq!(frame, "ab   │ x\ncd   │\n".as_bytes());
+
+        // Wrapped at the column width.
+        frame.clear();
+        side_by_side(b"abcde", b"xyzw", 10, &mut frame);
+        assert_eq!(frame, "abcd │ xyz\ne    │ w\n".as_bytes());
+    }
+
+    #[test]
+    fn rows() {
+        assert_eq!(n_rows(b"", 4), 0);
+        assert_eq!(n_rows(b"\n\n", 4), 2);
+        assert_eq!(n_rows(b"aaaa\naaaaa", 4), 3);
+        assert_eq!(n_rows(b"\x1b[1;31maaaa\x1b[0m\n", 4), 1);
+        assert_eq!(n_rows("✓✓✓\n".as_bytes(), 2), 2);
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::{bail, Result};
+use crossterm::{style::Color, Command, QueueableCommand};
+use serde::Deserialize;
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+};
+
+use crate::config::{CompletionConfig, ThemeConfig};
+
+/// The name of the environment variable to choose the base theme.
+const THEME_ENV_VAR: &str = "RUSTLINGS_THEME";
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeBase {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionPreset {
+    #[default]
+    Default,
+    /// Without emojis and box-drawing characters for terminals that render them poorly.
+    Ascii,
+}
+
+/// The texts shown when an exercise or all exercises are done.
+#[derive(Clone, Copy)]
+pub struct Completion {
+    /// Shown next to "Exercise done" and as the done state in the status line.
+    pub glyph: &'static str,
+    /// Shown after the key of the `next` command if set.
+    /// Otherwise, the translated message is shown followed by `emoji`.
+    pub move_on: Option<&'static str>,
+    pub emoji: &'static str,
+    /// Replaces the Fe-nish line above the final message of the exercises if set.
+    pub final_banner: Option<&'static str>,
+}
+
+impl Completion {
+    const DEFAULT: Self = Self {
+        glyph: "✓",
+        move_on: None,
+        emoji: "🦀",
+        final_banner: None,
+    };
+
+    const ASCII: Self = Self {
+        glyph: "[OK]",
+        move_on: None,
+        emoji: "",
+        final_banner: Some(ASCII_FINISH_LINE),
+    };
+
+    /// Build the texts from the config file.
+    /// The configured texts are leaked because they live until the end of the program.
+    pub fn build(config: &CompletionConfig) -> Self {
+        let mut completion = match config.preset.unwrap_or_default() {
+            CompletionPreset::Default => Self::DEFAULT,
+            CompletionPreset::Ascii => Self::ASCII,
+        };
+
+        if let Some(glyph) = &config.glyph {
+            completion.glyph = glyph.clone().leak();
+        }
+        if let Some(move_on) = &config.move_on {
+            completion.move_on = Some(move_on.clone().leak());
+        }
+        if let Some(final_message) = &config.final_message {
+            let final_message = final_message.trim_end();
+            completion.final_banner = Some(format!("{final_message}\n\n").leak());
+        }
+
+        completion
+    }
+}
+
+/// The colors used in the watch mode.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    /// The "Exercise done" message.
+    pub done: Color,
+    /// The heading of a hint.
+    pub hint: Color,
+    /// The heading of compiler warnings.
+    pub warning: Color,
+    /// The filled part of the progress bar.
+    pub progress_done: Color,
+    /// The remaining part of the progress bar.
+    pub progress_pending: Color,
+    /// Lines of the solution missing in the exercise file.
+    pub diff_added: Color,
+    /// Lines of the exercise file missing in the solution.
+    pub diff_removed: Color,
+    pub completion: Completion,
+    // If false, no colors or text attributes are emitted.
+    styled: bool,
+    // Plain text without graphical elements like emojis for screen readers.
+    a11y: bool,
+}
+
+impl Theme {
+    const DARK: Self = Self {
+        done: Color::Green,
+        hint: Color::Cyan,
+        warning: Color::Yellow,
+        progress_done: Color::Green,
+        progress_pending: Color::Red,
+        diff_added: Color::Green,
+        diff_removed: Color::Red,
+        completion: Completion::DEFAULT,
+        styled: true,
+        a11y: false,
+    };
+
+    // The light colors are easier to read on a white background.
+    const LIGHT: Self = Self {
+        done: Color::DarkGreen,
+        hint: Color::DarkBlue,
+        warning: Color::DarkYellow,
+        progress_done: Color::DarkGreen,
+        progress_pending: Color::DarkRed,
+        diff_added: Color::DarkGreen,
+        diff_removed: Color::DarkRed,
+        completion: Completion::DEFAULT,
+        styled: true,
+        a11y: false,
+    };
+
+    /// Build the theme from the config file.
+    /// The environment variable `RUSTLINGS_THEME` overwrites the base theme in the config file.
+    /// Styling is disabled by `no_color`, a non-empty `NO_COLOR` environment variable
+    /// (see https://no-color.org) or if stdout isn't a terminal.
+    /// `a11y` also disables styling and replaces graphical elements with plain text.
+    pub fn build(
+        config: &ThemeConfig,
+        completion: Completion,
+        no_color: bool,
+        a11y: bool,
+    ) -> Result<Self> {
+        let base = match env::var(THEME_ENV_VAR) {
+            Ok(value) => match value.as_str() {
+                "dark" => ThemeBase::Dark,
+                "light" => ThemeBase::Light,
+                _ => bail!(
+                    "Invalid value `{value}` of the environment variable `{THEME_ENV_VAR}`. Expected `dark` or `light`"
+                ),
+            },
+            Err(_) => config.base.unwrap_or_default(),
+        };
+
+        let mut theme = match base {
+            ThemeBase::Dark => Self::DARK,
+            ThemeBase::Light => Self::LIGHT,
+        };
+
+        for (color, config_color, field) in [
+            (&mut theme.done, &config.done, "done"),
+            (&mut theme.hint, &config.hint, "hint"),
+            (&mut theme.warning, &config.warning, "warning"),
+            (
+                &mut theme.progress_done,
+                &config.progress_done,
+                "progress_done",
+            ),
+            (
+                &mut theme.progress_pending,
+                &config.progress_pending,
+                "progress_pending",
+            ),
+            (&mut theme.diff_added, &config.diff_added, "diff_added"),
+            (
+                &mut theme.diff_removed,
+                &config.diff_removed,
+                "diff_removed",
+            ),
+        ] {
+            if let Some(config_color) = config_color {
+                let Ok(parsed) = Color::try_from(config_color.as_str()) else {
+                    bail!("Invalid color `{config_color}` of `theme.{field}` in the config file");
+                };
+                *color = parsed;
+            }
+        }
+
+        theme.completion = completion;
+        theme.a11y = a11y;
+        theme.styled = !a11y
+            && !no_color
+            && !env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+            && io::stdout().is_terminal();
+
+        Ok(theme)
+    }
+
+    #[inline]
+    pub fn styled(&self) -> bool {
+        self.styled
+    }
+
+    #[inline]
+    pub fn a11y(&self) -> bool {
+        self.a11y
+    }
+
+    /// Queue a command that changes the color or text attributes unless styling is disabled.
+    #[inline]
+    pub fn queue_style(&self, writer: &mut impl Write, command: impl Command) -> io::Result<()> {
+        if self.styled {
+            writer.queue(command)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+const ASCII_FINISH_LINE: &str = "+----------------------------------------------------+
+|          You made it to the finish line!           |
++----------------------------------------------------+
+
+";
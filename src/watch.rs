@@ -1,27 +1,41 @@
-use anyhow::{Error, Result};
+use anyhow::{Context, Error, Result};
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use std::{
     io::{self, Write},
+    mem,
     path::Path,
     sync::{
-        atomic::{AtomicBool, Ordering::Relaxed},
-        mpsc::channel,
+        atomic::{AtomicBool, AtomicU64, Ordering::Relaxed},
+        mpsc::{channel, Sender},
     },
+    thread,
     time::Duration,
 };
 
 use crate::{
     app_state::{AppState, ExercisesProgress},
+    info_file::InfoFile,
+    keys::Keys,
     list,
+    messages::Messages,
+    term::press_enter_prompt,
 };
 
 use self::{notify_event::NotifyEventHandler, state::WatchState, terminal_event::InputEvent};
 
+pub use self::{notify_event::DEFAULT_DEBOUNCE_DURATION, progress_event::EventStream};
+
+/// The default number of solution lines shown by the `peek` command.
+pub const DEFAULT_PEEK_LINES: usize = 5;
+
 mod notify_event;
+mod progress_event;
 mod state;
 mod terminal_event;
 
 static EXERCISE_RUNNING: AtomicBool = AtomicBool::new(false);
+// Incremented on every key press to cancel a scheduled auto-advance.
+static AUTO_ADVANCE_ID: AtomicU64 = AtomicU64::new(0);
 
 // Private unit type to force using the constructor function.
 #[must_use = "When the guard is dropped, the input is unpaused"]
@@ -44,12 +58,92 @@ impl Drop for InputPauseGuard {
 
 enum WatchEvent {
     Input(InputEvent),
-    FileChange { exercise_ind: usize },
-    TerminalResize { width: u16 },
+    FileChange {
+        exercise_ind: usize,
+    },
+    /// Sent some time after the current exercise is done if auto-advance is enabled.
+    AutoAdvance {
+        id: u64,
+    },
+    /// Sent periodically if an interval is set to check the current exercise for changes.
+    Interval,
+    TerminalResize {
+        width: u16,
+    },
     NotifyErr(notify::Error),
     TerminalEventErr(io::Error),
 }
 
+const MAX_HISTORY_LEN: usize = 32;
+
+/// The exercises that were left by jumping to another one (e.g. by checking all exercises or
+/// in the list) to be able to return to them.
+#[derive(Default)]
+struct NavHistory(Vec<usize>);
+
+impl NavHistory {
+    fn push(&mut self, exercise_ind: usize) {
+        // Only the most recent jumps are relevant.
+        if self.0.len() == MAX_HISTORY_LEN {
+            self.0.remove(0);
+        }
+        self.0.push(exercise_ind);
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<usize> {
+        self.0.pop()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Options of the watch mode that don't change while it runs.
+#[derive(Clone, Copy)]
+pub struct WatchOptions {
+    /// Ask for a confirmation before quitting if there are pending exercises.
+    pub confirm_quit: bool,
+    /// Move on to the next exercise shortly after the current one is done.
+    pub auto_advance: bool,
+    /// The keys of the commands.
+    pub keys: Keys,
+    /// Hide the prompt until a key is pressed.
+    pub focus_mode: bool,
+    /// Show a desktop notification when a long run of an exercise is done.
+    pub notify: bool,
+    /// Animate a spinner while checking an exercise.
+    pub spinner: bool,
+    /// Enable commands for developing exercises (e.g. marking all exercises as done).
+    pub dev: bool,
+    /// The user-facing strings in the selected language.
+    pub messages: &'static Messages,
+    /// The time to wait for more changes of an exercise file before running it.
+    pub debounce: Duration,
+    /// Only render one line with the current exercise and the progress (e.g. for tmux).
+    pub status_line: bool,
+    /// Redraw only the changed lines after small changes like toggles.
+    pub low_bandwidth: bool,
+    /// The number of solution lines shown by the `peek` command. `0` disables the command.
+    pub peek_lines: usize,
+    /// Allow peeking at the solution before the exercise is done.
+    pub peek_while_pending: bool,
+    /// Progress events are sent to this stream if it is set.
+    pub events: Option<&'static EventStream>,
+    /// Explain common compiler errors in the output.
+    pub error_notes: bool,
+    /// Make the hints inaccessible for challenging oneself. Can't be toggled in the session.
+    pub no_hints: bool,
+    /// Check the current exercise for changes with this period even without file events.
+    pub interval: Option<Duration>,
+    /// Show the hints in a second column beside the output on wide terminals.
+    pub two_columns: bool,
+    /// Show the diagnostics of rust-analyzer below the output of failing exercises.
+    pub rust_analyzer: bool,
+}
+
 /// Returned by the watch mode to indicate what to do afterwards.
 #[must_use]
 enum WatchExit {
@@ -57,11 +151,37 @@ enum WatchExit {
     Shutdown,
     /// Enter the list mode and restart the watch mode afterwards.
     List,
+    /// Reload the info of all exercises and restart the watch mode afterwards.
+    Reload,
+}
+
+// Send an interval event periodically until the watch mode exits and drops the receiver.
+fn spawn_interval_timer(sender: Sender<WatchEvent>, interval: Duration) -> Result<()> {
+    thread::Builder::new()
+        .spawn(move || loop {
+            thread::sleep(interval);
+
+            // The exercise is checked after the run anyway if it changed in the meantime.
+            if EXERCISE_RUNNING.load(Relaxed) {
+                continue;
+            }
+
+            if sender.send(WatchEvent::Interval).is_err() {
+                break;
+            }
+        })
+        .context("Failed to spawn a thread for the interval timer")?;
+
+    Ok(())
 }
 
+// The warnings are shown with the first run of the current exercise.
 fn run_watch(
     app_state: &mut AppState,
+    history: &mut NavHistory,
     notify_exercise_names: Option<&'static [&'static [u8]]>,
+    options: WatchOptions,
+    warnings: Vec<String>,
 ) -> Result<WatchExit> {
     let (watch_event_sender, watch_event_receiver) = channel();
 
@@ -69,8 +189,11 @@ fn run_watch(
     // Prevent dropping the guard until the end of the function.
     // Otherwise, the file watcher exits.
     let _watcher_guard = if let Some(exercise_names) = notify_exercise_names {
-        let notify_event_handler =
-            NotifyEventHandler::build(watch_event_sender.clone(), exercise_names)?;
+        let notify_event_handler = NotifyEventHandler::build(
+            watch_event_sender.clone(),
+            exercise_names,
+            options.debounce,
+        )?;
 
         let mut watcher = RecommendedWatcher::new(
             notify_event_handler,
@@ -88,21 +211,64 @@ fn run_watch(
         None
     };
 
-    let mut watch_state = WatchState::build(app_state, watch_event_sender, manual_run)?;
+    if let Some(interval) = options.interval {
+        spawn_interval_timer(watch_event_sender.clone(), interval)?;
+    }
+
+    let mut watch_state =
+        WatchState::build(app_state, history, watch_event_sender, manual_run, options)?;
     let mut stdout = io::stdout().lock();
 
+    watch_state.show_warnings(&warnings);
     watch_state.run_current_exercise(&mut stdout)?;
 
     while let Ok(event) = watch_event_receiver.recv() {
+        let mut prompt_was_hidden = false;
+        if let WatchEvent::Input(_) = event {
+            prompt_was_hidden = watch_state.reveal_prompt();
+        }
+
         match event {
             WatchEvent::Input(InputEvent::Next) => match watch_state.next_exercise(&mut stdout)? {
                 ExercisesProgress::AllDone => break,
                 ExercisesProgress::NewPending => watch_state.run_current_exercise(&mut stdout)?,
                 ExercisesProgress::CurrentPending => (),
             },
-            WatchEvent::Input(InputEvent::Run) => watch_state.run_current_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Prev) => watch_state.prev_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Back) => watch_state.go_back(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Skip) => watch_state.skip_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Run) => {
+                watch_state.rerun_current_exercise(&mut stdout)?
+            }
             WatchEvent::Input(InputEvent::Hint) => watch_state.show_hint(&mut stdout)?,
-            WatchEvent::Input(InputEvent::List) => return Ok(WatchExit::List),
+            WatchEvent::Input(InputEvent::ToggleHints) => {
+                watch_state.toggle_hints(&mut stdout)?;
+            }
+            WatchEvent::Input(InputEvent::HintPager) => watch_state.show_hint_pager(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Bookmark) => watch_state.toggle_bookmark(&mut stdout)?,
+            WatchEvent::Input(InputEvent::CopySolution) => {
+                watch_state.copy_solution(&mut stdout)?
+            }
+            WatchEvent::Input(InputEvent::Peek) => watch_state.toggle_peek(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Diff) => watch_state.toggle_diff(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Note) => watch_state.add_note(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Report) => watch_state.report_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Groups) => watch_state.toggle_groups(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Stats) => watch_state.show_stats(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Wrap) => watch_state.toggle_wrap(&mut stdout)?,
+            WatchEvent::Input(InputEvent::PauseWatcher) => {
+                watch_state.toggle_watcher_pause(&mut stdout)?;
+            }
+            WatchEvent::Input(InputEvent::Redraw) => watch_state.render(&mut stdout)?,
+            WatchEvent::Input(InputEvent::View) => watch_state.view_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Edit) => watch_state.edit_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::List) => {
+                watch_state.record_time_spent()?;
+                return Ok(WatchExit::List);
+            }
+            WatchEvent::Input(InputEvent::Check) => {
+                watch_state.check_current_exercise(&mut stdout)?
+            }
             WatchEvent::Input(InputEvent::CheckAll) => match watch_state
                 .check_all_exercises(&mut stdout)?
             {
@@ -110,14 +276,46 @@ fn run_watch(
                 ExercisesProgress::NewPending => watch_state.run_current_exercise(&mut stdout)?,
                 ExercisesProgress::CurrentPending => watch_state.render(&mut stdout)?,
             },
+            WatchEvent::Input(InputEvent::NextFailure) => watch_state.next_failure(&mut stdout)?,
             WatchEvent::Input(InputEvent::Reset) => watch_state.reset_exercise(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Undo) => watch_state.undo_reset(&mut stdout)?,
+            WatchEvent::Input(InputEvent::Reload) => {
+                watch_state.record_time_spent()?;
+                return Ok(WatchExit::Reload);
+            }
             WatchEvent::Input(InputEvent::Quit) => {
-                stdout.write_all(QUIT_MSG)?;
+                if watch_state.confirm_quit(&mut stdout)? {
+                    stdout.write_all(QUIT_MSG)?;
+                    break;
+                }
+            }
+            WatchEvent::Input(InputEvent::Other) => {
+                if prompt_was_hidden {
+                    watch_state.rerender(&mut stdout)?;
+                }
+            }
+            WatchEvent::Input(InputEvent::FinishAll) => {
+                watch_state.finish_all_exercises(&mut stdout)?;
                 break;
             }
+            WatchEvent::AutoAdvance { id } => {
+                // A key was pressed or the exercise was rerun in the meantime.
+                if AUTO_ADVANCE_ID.load(Relaxed) != id {
+                    continue;
+                }
+
+                match watch_state.next_exercise(&mut stdout)? {
+                    ExercisesProgress::AllDone => break,
+                    ExercisesProgress::NewPending => {
+                        watch_state.run_current_exercise(&mut stdout)?
+                    }
+                    ExercisesProgress::CurrentPending => (),
+                }
+            }
             WatchEvent::FileChange { exercise_ind } => {
                 watch_state.handle_file_change(exercise_ind, &mut stdout)?;
             }
+            WatchEvent::Interval => watch_state.handle_interval(&mut stdout)?,
             WatchEvent::TerminalResize { width } => {
                 watch_state.update_term_width(width, &mut stdout)?;
             }
@@ -128,20 +326,83 @@ fn run_watch(
         }
     }
 
+    watch_state.record_time_spent()?;
+
     Ok(WatchExit::Shutdown)
 }
 
+/// The exercise names for the notify event handler thread.
+pub fn notify_exercise_names(app_state: &AppState) -> &'static [&'static [u8]] {
+    // Leaking is not a problem because the slice lives until the end of the program.
+    // A new slice is only leaked when reloading the exercises which is rare.
+    app_state
+        .exercises()
+        .iter()
+        .map(|exercise| exercise.name.as_bytes())
+        .collect::<Vec<_>>()
+        .leak()
+}
+
+// Reload the exercises' info. Errors while parsing `info.toml` are only shown to the user
+// because they are expected while editing the file.
+// Returns the warnings about the new exercise infos.
+fn reload(app_state: &mut AppState) -> Result<Vec<String>> {
+    let info_file = match InfoFile::parse().and_then(|info_file| {
+        info_file.check_format_version()?;
+        Ok(info_file)
+    }) {
+        Ok(v) => v,
+        Err(e) => {
+            let mut stdout = io::stdout().lock();
+            writeln!(stdout, "\n\nFailed to reload the exercises: {e:?}")?;
+            stdout.write_all(b"\nPress ENTER to continue without reloading ")?;
+            press_enter_prompt(&mut stdout)?;
+            return Ok(Vec::new());
+        }
+    };
+
+    app_state.reload(
+        info_file.exercises,
+        info_file.final_message.unwrap_or_default(),
+    )
+}
+
 fn watch_list_loop(
     app_state: &mut AppState,
-    notify_exercise_names: Option<&'static [&'static [u8]]>,
+    mut notify_exercise_names: Option<&'static [&'static [u8]]>,
+    options: WatchOptions,
 ) -> Result<()> {
+    let mut history = NavHistory::default();
+    let mut warnings = Vec::new();
+
     loop {
-        match run_watch(app_state, notify_exercise_names)? {
+        match run_watch(
+            app_state,
+            &mut history,
+            notify_exercise_names,
+            options,
+            mem::take(&mut warnings),
+        )? {
             WatchExit::Shutdown => break Ok(()),
             // It is much easier to exit the watch mode, launch the list mode and then restart
             // the watch mode instead of trying to pause the watch threads and correct the
             // watch state.
-            WatchExit::List => list::list(app_state)?,
+            WatchExit::List => {
+                let exercise_ind = app_state.current_exercise_ind();
+                list::list(app_state)?;
+                if app_state.current_exercise_ind() != exercise_ind {
+                    history.push(exercise_ind);
+                }
+            }
+            WatchExit::Reload => {
+                warnings = reload(app_state)?;
+                history = NavHistory::default();
+
+                // The indices of the exercises might have changed.
+                if notify_exercise_names.is_some() {
+                    notify_exercise_names = Some(self::notify_exercise_names(app_state));
+                }
+            }
         }
     }
 }
@@ -150,6 +411,7 @@ fn watch_list_loop(
 pub fn watch(
     app_state: &mut AppState,
     notify_exercise_names: Option<&'static [&'static [u8]]>,
+    options: WatchOptions,
 ) -> Result<()> {
     #[cfg(not(windows))]
     {
@@ -161,7 +423,7 @@ pub fn watch(
             rustix::termios::LocalModes::ICANON | rustix::termios::LocalModes::ECHO;
         rustix::termios::tcsetattr(stdin_fd, rustix::termios::OptionalActions::Now, &termios)?;
 
-        let res = watch_list_loop(app_state, notify_exercise_names);
+        let res = watch_list_loop(app_state, notify_exercise_names, options);
 
         termios.local_modes = original_local_modes;
         rustix::termios::tcsetattr(stdin_fd, rustix::termios::OptionalActions::Now, &termios)?;
@@ -170,7 +432,7 @@ pub fn watch(
     }
 
     #[cfg(windows)]
-    watch_list_loop(app_state, notify_exercise_names)
+    watch_list_loop(app_state, notify_exercise_names, options)
 }
 
 const QUIT_MSG: &[u8] = b"
@@ -14,7 +14,7 @@ use std::{
 
 use super::{WatchEvent, EXERCISE_RUNNING};
 
-const DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
+pub const DEFAULT_DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
 
 pub struct NotifyEventHandler {
     error_sender: Sender<WatchEvent>,
@@ -25,9 +25,12 @@ pub struct NotifyEventHandler {
 }
 
 impl NotifyEventHandler {
+    /// Changes are collected until no change happened for the `debounce` duration.
+    /// Only the first change after such a quiet period is sent immediately.
     pub fn build(
         watch_event_sender: Sender<WatchEvent>,
         exercise_names: &'static [&'static [u8]],
+        debounce: Duration,
     ) -> Result<Self> {
         let (update_sender, update_receiver) = sync_channel(0);
         let error_sender = watch_event_sender.clone();
@@ -36,11 +39,28 @@ impl NotifyEventHandler {
         thread::Builder::new()
             .spawn(move || {
                 let mut exercise_updated = vec![false; exercise_names.len()];
+                let mut quiet = true;
 
                 loop {
-                    match update_receiver.recv_timeout(DEBOUNCE_DURATION) {
-                        Ok(exercise_ind) => exercise_updated[exercise_ind] = true,
+                    match update_receiver.recv_timeout(debounce) {
+                        Ok(exercise_ind) => {
+                            if quiet {
+                                // Don't delay the first change.
+                                if watch_event_sender
+                                    .send(WatchEvent::FileChange { exercise_ind })
+                                    .is_err()
+                                {
+                                    break;
+                                }
+                            } else {
+                                exercise_updated[exercise_ind] = true;
+                            }
+
+                            quiet = false;
+                        }
                         Err(RecvTimeoutError::Timeout) => {
+                            quiet = true;
+
                             for (exercise_ind, updated) in exercise_updated.iter_mut().enumerate() {
                                 if *updated {
                                     if watch_event_sender
@@ -110,7 +130,9 @@ impl notify::EventHandler for NotifyEventHandler {
                 },
                 AccessKind::Read | AccessKind::Open(_) | AccessKind::Other => return,
             },
-            EventKind::Create(_) | EventKind::Remove(_) | EventKind::Other => return,
+            // Show that the file is missing.
+            EventKind::Remove(_) => (),
+            EventKind::Create(_) | EventKind::Other => return,
         }
 
         let _ = input_event
@@ -0,0 +1,76 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::{fs::OpenOptions, io::Write, path::Path, sync::Mutex};
+
+/// A progress event of the watch mode. Serialized as one JSON object per line.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum ProgressEvent<'a> {
+    /// Checking the exercise started.
+    Started {
+        exercise: &'a str,
+    },
+    Passed {
+        exercise: &'a str,
+    },
+    Failed {
+        exercise: &'a str,
+    },
+    /// Moved on to the next exercise.
+    Advanced {
+        exercise: &'a str,
+    },
+}
+
+/// Sends progress events to external tools like editor integrations.
+pub struct EventStream {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventStream {
+    /// Connect to the Unix socket or open the named pipe at `path`.
+    /// Opening a named pipe blocks until the other side opens it for reading.
+    /// The stream is leaked because it lives until the end of the program.
+    pub fn connect(path: &Path) -> Result<&'static Self> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::{fs::FileTypeExt, net::UnixStream};
+
+            if path
+                .metadata()
+                .is_ok_and(|metadata| metadata.file_type().is_socket())
+            {
+                let stream = UnixStream::connect(path).with_context(|| {
+                    format!("Failed to connect to the socket `{}`", path.display())
+                })?;
+                return Ok(Self::leak(Box::new(stream)));
+            }
+        }
+
+        let file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .with_context(|| format!("Failed to open the named pipe `{}`", path.display()))?;
+
+        Ok(Self::leak(Box::new(file)))
+    }
+
+    fn leak(writer: Box<dyn Write + Send>) -> &'static Self {
+        Box::leak(Box::new(Self {
+            writer: Mutex::new(writer),
+        }))
+    }
+
+    pub fn send(&self, event: &ProgressEvent) {
+        let Ok(mut writer) = self.writer.lock() else {
+            return;
+        };
+
+        let Ok(mut line) = serde_json::to_vec(event) else {
+            return;
+        };
+        line.push(b'\n');
+        // The other side might have disconnected. This shouldn't affect the watch mode.
+        let _ = writer.write_all(&line).and_then(|()| writer.flush());
+    }
+}
@@ -1,29 +1,205 @@
 use anyhow::{Context, Result};
 use crossterm::{
-    style::{
-        Attribute, Attributes, Color, ResetColor, SetAttribute, SetAttributes, SetForegroundColor,
-    },
-    terminal, QueueableCommand,
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::{Attribute, Attributes, ResetColor, SetAttribute, SetAttributes, SetForegroundColor},
+    terminal::{self, Clear, ClearType},
+    QueueableCommand,
 };
 use std::{
+    collections::HashMap,
+    env, fs,
+    hash::{DefaultHasher, Hash, Hasher},
     io::{self, Read, StdoutLock, Write},
-    sync::mpsc::{sync_channel, Sender, SyncSender},
+    path::Path,
+    process::Command,
+    sync::{
+        atomic::Ordering::Relaxed,
+        mpsc::{sync_channel, Sender, SyncSender},
+    },
     thread,
+    time::{Duration, Instant, SystemTime},
 };
 
 use crate::{
-    app_state::{AppState, ExercisesProgress},
+    app_state::{AppState, ExercisesProgress, REPORTS_FILE_NAME, TREND_DAYS},
     clear_terminal,
-    exercise::{solution_link_line, RunnableExercise, OUTPUT_CAPACITY},
-    term::progress_bar,
+    cmd::{clipboard_command_names, copy_to_clipboard, send_desktop_notification},
+    diff::{line_diff, DiffLine},
+    error_notes::error_notes,
+    exercise::{solution_link_line, split_off_warnings, OUTPUT_CAPACITY},
+    info_file::Difficulty,
+    keys::Keys,
+    messages::{quoted_list, Messages},
+    rust_analyzer::{self, Diagnostic},
+    term::{
+        self, press_enter_prompt, progress_bar, side_by_side, sparkline, start_spinner,
+        wrap_output, write_duration, CountedWrite, MaxLenWriter,
+    },
+    theme::Theme,
 };
 
-use super::{terminal_event::terminal_event_handler, InputPauseGuard, WatchEvent};
+use super::{
+    progress_event::{EventStream, ProgressEvent},
+    terminal_event::terminal_event_handler,
+    InputPauseGuard, NavHistory, WatchEvent, WatchOptions, AUTO_ADVANCE_ID,
+};
 
 const HEADING_ATTRIBUTES: Attributes = Attributes::none()
     .with(Attribute::Bold)
     .with(Attribute::Underlined);
 
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+
+// Time between finishing an exercise and advancing to the next one with auto-advance.
+const AUTO_ADVANCE_DELAY: Duration = Duration::from_secs(2);
+
+// Runs taking at least this long trigger a desktop notification if enabled.
+const NOTIFY_THRESHOLD: Duration = Duration::from_secs(5);
+// The hints are only shown in a second column if the terminal is at least this wide.
+const TWO_COLUMNS_MIN_WIDTH: u16 = 160;
+
+// Read a line from the user and echo it. Returns `None` if cancelled with ESC.
+fn read_line(stdout: &mut StdoutLock) -> Result<Option<String>> {
+    stdout.flush()?;
+
+    // The terminal doesn't echo the input in the watch mode.
+    let mut line = Vec::new();
+    let saved = {
+        let mut stdin = io::stdin().lock();
+        let mut byte = [0];
+        loop {
+            stdin
+                .read_exact(&mut byte)
+                .context("Failed to read the user's input")?;
+
+            match byte[0] {
+                b'\n' | b'\r' => break true,
+                // ESC
+                0x1b => break false,
+                // Backspace or DEL
+                0x08 | 0x7f => {
+                    // Remove the whole last UTF-8 character.
+                    while let Some(byte) = line.pop() {
+                        if byte & 0b1100_0000 != 0b1000_0000 {
+                            break;
+                        }
+                    }
+                    stdout.write_all(b"\x08 \x08")?;
+                }
+                byte => {
+                    line.push(byte);
+                    stdout.write_all(&[byte])?;
+                }
+            }
+            stdout.flush()?;
+        }
+    };
+
+    Ok(saved.then(|| String::from_utf8_lossy(&line).into_owned()))
+}
+
+// Show the text in a scrollable view until a key other than a scroll key is pressed.
+// Without line numbers, the lines are wrapped at the terminal width.
+fn pager(
+    stdout: &mut StdoutLock,
+    text: &str,
+    title: &str,
+    line_numbers: bool,
+    theme: &Theme,
+) -> Result<()> {
+    let mut wrapped = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let (width, height) = term::term_size();
+        // The last row is for the footer.
+        let n_rows = usize::from(height.saturating_sub(1)).max(1);
+
+        let wrapped_text;
+        let text = if line_numbers {
+            text
+        } else {
+            wrap_output(text.as_bytes(), usize::from(width), &mut wrapped);
+            wrapped_text = String::from_utf8_lossy(&wrapped);
+            &wrapped_text
+        };
+        let lines = text.lines().collect::<Vec<_>>();
+        offset = offset.min(lines.len().saturating_sub(n_rows));
+
+        clear_terminal(stdout)?;
+        for (line_ind, line) in lines.iter().enumerate().skip(offset).take(n_rows) {
+            let mut writer = MaxLenWriter::new(stdout, usize::from(width));
+            if line_numbers {
+                theme.queue_style(writer.stdout, SetAttribute(Attribute::Dim))?;
+                // Integers are in ASCII.
+                writer.write_ascii(format!("{:>4} ", line_ind + 1).as_bytes())?;
+                theme.queue_style(writer.stdout, ResetColor)?;
+            }
+            writer.write_str(line)?;
+            stdout.write_all(b"\n")?;
+        }
+
+        theme.queue_style(stdout, SetAttribute(Attribute::Bold))?;
+        write!(
+            stdout,
+            "{title} {}-{}/{}",
+            (offset + 1).min(lines.len()),
+            (offset + n_rows).min(lines.len()),
+            lines.len(),
+        )?;
+        theme.queue_style(stdout, ResetColor)?;
+        stdout.write_all(" ↑/↓/PgUp/PgDn:scroll, any other key:return ".as_bytes())?;
+        stdout.flush()?;
+
+        let key = match event::read().context("Failed to read the user's input")? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            // Rendered again with the new size.
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => offset = offset.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => offset += 1,
+            KeyCode::PageUp => offset = offset.saturating_sub(n_rows),
+            KeyCode::PageDown => offset += n_rows,
+            KeyCode::Home => offset = 0,
+            KeyCode::End => offset = lines.len(),
+            _ => break,
+        }
+    }
+
+    Ok(())
+}
+
+// The result of the last run of an exercise.
+struct CachedRun {
+    // Hash of the exercise file's content when it was run.
+    source_hash: u64,
+    // Modification time of the exercise file when its hash was last computed.
+    mtime: Option<SystemTime>,
+    success: bool,
+    output: Vec<u8>,
+    warnings: Vec<u8>,
+}
+
+fn file_mtime(path: &str) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn file_hash(path: &str) -> Option<u64> {
+    fs::read(path).ok().map(|source| {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        hasher.finish()
+    })
+}
+
 #[derive(PartialEq, Eq)]
 enum DoneStatus {
     DoneWithSolution(String),
@@ -33,65 +209,318 @@ enum DoneStatus {
 
 pub struct WatchState<'a> {
     app_state: &'a mut AppState,
+    // Kept while switching to the list.
+    history: &'a mut NavHistory,
     output: Vec<u8>,
-    show_hint: bool,
+    // Compiler warnings moved out of `output` to show them separately.
+    warnings: Vec<u8>,
+    // Of rust-analyzer for the last failing run if enabled.
+    diagnostics: Vec<Diagnostic>,
+    // The number of hints of the current exercise that are revealed inline or in the pager.
+    n_shown_hints: usize,
     done_status: DoneStatus,
+    // The exercise index and the number of its consecutive failed runs.
+    failing_exercise: Option<(usize, u32)>,
+    // The number of failed runs before the last run passed. Shown once like `message`.
+    fixed_after: Option<u32>,
     manual_run: bool,
+    confirm_quit: bool,
     term_width: u16,
     terminal_event_unpause_sender: SyncSender<()>,
+    // Start of the time not yet added to the time spent on the current exercise.
+    timer_start: Instant,
+    // Shown above the prompt once.
+    message: String,
+    // Show the progress of every exercise group below the progress bar.
+    show_groups: bool,
+    // Wrap the lines of the output at the terminal width.
+    wrap_output: bool,
+    // The rendered diff between the exercise file and its solution if it is shown.
+    diff: Option<Vec<u8>>,
+    // Exercise indices to their last run to skip rerunning exercises with unchanged files.
+    run_cache: HashMap<usize, CachedRun>,
+    // Only set if auto-advance is enabled.
+    auto_advance_sender: Option<Sender<WatchEvent>>,
+    keys: Keys,
+    // Hide the prompt until a key is pressed.
+    focus_mode: bool,
+    // Only relevant in the focus mode. Reset when the exercise is run.
+    prompt_revealed: bool,
+    // Show a desktop notification after long runs.
+    notify: bool,
+    // Animate a spinner while checking the exercise.
+    spinner: bool,
+    messages: &'static Messages,
+    // Only render a single status line.
+    status_line: bool,
+    low_bandwidth: bool,
+    // The last rendered frame. Cleared if something else was written to the screen since.
+    last_frame: Vec<u8>,
+    // The current exercise file was deleted. It can be restored by resetting the exercise.
+    file_missing: bool,
+    events: Option<&'static EventStream>,
+    // The first lines of the solution if they are shown.
+    peek: Option<String>,
+    peek_lines: usize,
+    peek_while_pending: bool,
+    error_notes: bool,
+    two_columns: bool,
+    rust_analyzer: bool,
+    // Set by `--no-hints`. The hints can't be shown again in the session.
+    hints_disabled: bool,
+    // Toggled in the session.
+    hints_hidden: bool,
+    // Exercises that failed in the last check of all exercises to step through them.
+    failures: Vec<usize>,
+    // Changes of the exercise files are ignored until the watcher is resumed.
+    watcher_paused: bool,
 }
 
 impl<'a> WatchState<'a> {
     pub fn build(
         app_state: &'a mut AppState,
+        history: &'a mut NavHistory,
         watch_event_sender: Sender<WatchEvent>,
         manual_run: bool,
+        options: WatchOptions,
     ) -> Result<Self> {
-        let term_width = terminal::size()
-            .context("Failed to get the terminal size")?
-            .0;
+        let term_width = term::term_width();
 
         let (terminal_event_unpause_sender, terminal_event_unpause_receiver) = sync_channel(0);
+        let auto_advance_sender = options.auto_advance.then(|| watch_event_sender.clone());
+        // Only third-party exercises are read from an `info.toml` file.
+        let reload = !app_state.official_exercises();
 
         thread::Builder::new()
             .spawn(move || {
                 terminal_event_handler(
                     watch_event_sender,
                     terminal_event_unpause_receiver,
-                    manual_run,
+                    options.keys,
+                    options.dev,
+                    reload,
                 )
             })
             .context("Failed to spawn a thread to handle terminal events")?;
 
         Ok(Self {
             app_state,
+            history,
             output: Vec::with_capacity(OUTPUT_CAPACITY),
-            show_hint: false,
+            warnings: Vec::new(),
+            diagnostics: Vec::new(),
+            n_shown_hints: 0,
             done_status: DoneStatus::Pending,
+            failing_exercise: None,
+            fixed_after: None,
             manual_run,
+            confirm_quit: options.confirm_quit,
             term_width,
             terminal_event_unpause_sender,
+            timer_start: Instant::now(),
+            message: String::new(),
+            show_groups: false,
+            wrap_output: false,
+            diff: None,
+            run_cache: HashMap::new(),
+            auto_advance_sender,
+            keys: options.keys,
+            focus_mode: options.focus_mode,
+            prompt_revealed: false,
+            notify: options.notify,
+            spinner: options.spinner,
+            messages: options.messages,
+            status_line: options.status_line,
+            low_bandwidth: options.low_bandwidth,
+            last_frame: Vec::new(),
+            file_missing: false,
+            events: options.events,
+            peek: None,
+            peek_lines: options.peek_lines,
+            peek_while_pending: options.peek_while_pending,
+            error_notes: options.error_notes,
+            two_columns: options.two_columns,
+            rust_analyzer: options.rust_analyzer,
+            hints_disabled: options.no_hints,
+            hints_hidden: options.no_hints,
+            failures: Vec::new(),
+            watcher_paused: false,
         })
     }
 
+    fn send_event(&self, event: ProgressEvent) {
+        if let Some(events) = self.events {
+            events.send(&event);
+        }
+    }
+
+    /// Add the time since the last call to the time spent on the current exercise
+    /// if it is still pending.
+    /// Has to be called before changing the current exercise or its done status.
+    pub fn record_time_spent(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let duration = now - self.timer_start;
+        self.timer_start = now;
+
+        if self.done_status == DoneStatus::Pending && !self.app_state.current_exercise().done {
+            self.app_state
+                .add_time_spent(self.app_state.current_exercise_ind(), duration)?;
+        }
+
+        Ok(())
+    }
+
     pub fn run_current_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.run_current_exercise_with(stdout, false)
+    }
+
+    // `report_result` shows whether the exercise passes above the prompt instead of
+    // auto-advancing.
+    fn run_current_exercise_with(
+        &mut self,
+        stdout: &mut StdoutLock,
+        report_result: bool,
+    ) -> Result<()> {
         // Ignore any input until running the exercise is done.
         let _input_pause_guard = InputPauseGuard::scoped_pause();
 
-        self.show_hint = false;
+        // Cancel a scheduled auto-advance because the result might change.
+        AUTO_ADVANCE_ID.fetch_add(1, Relaxed);
 
-        writeln!(
-            stdout,
-            "\nChecking the exercise `{}`. Please wait…",
-            self.app_state.current_exercise().name,
-        )?;
+        self.n_shown_hints = 0;
+        self.diff = None;
+        self.peek = None;
+        self.prompt_revealed = false;
 
-        let success = self
-            .app_state
-            .current_exercise()
-            .run_exercise(Some(&mut self.output), self.app_state.cmd_runner())?;
-        self.output.push(b'\n');
+        let exercise_ind = self.app_state.current_exercise_ind();
+        let path = self.app_state.current_exercise().path;
+        // Reading the file is much cheaper than compiling the exercise.
+        let mtime = file_mtime(path);
+        let source_hash = file_hash(path);
+
+        self.file_missing = mtime.is_none() && !Path::new(path).exists();
+        if self.file_missing {
+            self.output.clear();
+            self.warnings.clear();
+            self.record_time_spent()?;
+            self.done_status = DoneStatus::Pending;
+            self.render(stdout)?;
+            self.message.clear();
+            return Ok(());
+        }
+
+        self.send_event(ProgressEvent::Started {
+            exercise: self.app_state.current_exercise().name,
+        });
+
+        let success = match self
+            .run_cache
+            .get(&exercise_ind)
+            .filter(|cached_run| Some(cached_run.source_hash) == source_hash)
+        {
+            Some(cached_run) => {
+                self.output.clear();
+                self.output.extend_from_slice(&cached_run.output);
+                self.warnings.clone_from(&cached_run.warnings);
+                cached_run.success
+            }
+            None => {
+                // The status line is only replaced by the result.
+                if !self.status_line {
+                    write!(
+                        stdout,
+                        "\n{} `{}`",
+                        self.messages.checking_exercise,
+                        self.app_state.current_exercise().name,
+                    )?;
+                    // Release builds are slower. Don't let that be a surprise.
+                    if self.app_state.cmd_runner().release() {
+                        write!(stdout, " {}", self.messages.in_release_mode)?;
+                    }
+                    writeln!(stdout, ". {}\n", self.messages.please_wait)?;
+                    stdout.flush()?;
+                }
+
+                // Stopped by the first live output or when the run is done.
+                let spinner_guard = if self.spinner && !self.status_line {
+                    Some(start_spinner()?)
+                } else {
+                    None
+                };
+                let run_start = Instant::now();
+
+                // Show the compiler output while waiting. It is rendered again afterwards.
+                let cmd_runner = self.app_state.cmd_runner();
+                cmd_runner.set_live_output(!self.status_line);
+                let res = cmd_runner
+                    .run_exercise(self.app_state.current_exercise(), Some(&mut self.output));
+                cmd_runner.set_live_output(false);
+                drop(spinner_guard);
+                let success = res?;
+                self.output.push(b'\n');
+
+                self.refresh_term_width();
+
+                // Short runs don't notify to avoid spam.
+                if self.notify && run_start.elapsed() >= NOTIFY_THRESHOLD {
+                    let name = self.app_state.current_exercise().name;
+                    let body = format!(
+                        "{} `{name}` {}",
+                        self.messages.the_exercise,
+                        if success {
+                            self.messages.passed
+                        } else {
+                            self.messages.failed
+                        },
+                    );
+                    // Degrades silently without notification support.
+                    send_desktop_notification("Rustlings", &body);
+                }
+
+                self.warnings = split_off_warnings(&mut self.output);
+
+                if let Some(source_hash) = source_hash {
+                    self.run_cache.insert(
+                        exercise_ind,
+                        CachedRun {
+                            source_hash,
+                            mtime,
+                            success,
+                            output: self.output.clone(),
+                            warnings: self.warnings.clone(),
+                        },
+                    );
+                }
+
+                success
+            }
+        };
+        self.record_time_spent()?;
+        // Only for failures to not slow down passing runs.
+        self.diagnostics = if self.rust_analyzer && !success {
+            rust_analyzer::diagnostics(self.app_state.current_exercise().path)
+        } else {
+            Vec::new()
+        };
+        let exercise = self.app_state.current_exercise().name;
+        self.send_event(if success {
+            ProgressEvent::Passed { exercise }
+        } else {
+            ProgressEvent::Failed { exercise }
+        });
+        // Only a transition from a failed run of the same exercise to a passing one.
+        let failed_runs = match self.failing_exercise {
+            Some((ind, n)) if ind == exercise_ind => n,
+            _ => 0,
+        };
         if success {
+            self.failing_exercise = None;
+            self.app_state
+                .refresh_done_mtime(self.app_state.current_exercise_ind())?;
+            if self.done_status == DoneStatus::Pending && failed_runs > 0 {
+                self.fixed_after = Some(failed_runs);
+            }
+
             self.done_status =
                 if let Some(solution_path) = self.app_state.current_solution_path()? {
                     DoneStatus::DoneWithSolution(solution_path)
@@ -102,19 +531,96 @@ impl<'a> WatchState<'a> {
             self.app_state
                 .set_pending(self.app_state.current_exercise_ind())?;
 
+            self.failing_exercise = Some((exercise_ind, failed_runs + 1));
             self.done_status = DoneStatus::Pending;
         }
 
+        if report_result {
+            self.message.push_str(if success {
+                self.messages.current_exercise_passes
+            } else {
+                self.messages.current_exercise_fails
+            });
+        } else if success && self.schedule_auto_advance()? {
+            self.message.push_str(self.messages.auto_advancing);
+        }
+
         self.render(stdout)?;
+        self.message.clear();
+        self.fixed_after = None;
+
         Ok(())
     }
 
+    // Send an auto-advance event after a delay unless this is the last pending required exercise.
+    // Return `true` if the auto-advance was scheduled.
+    fn schedule_auto_advance(&self) -> Result<bool> {
+        let Some(sender) = &self.auto_advance_sender else {
+            return Ok(false);
+        };
+
+        // Pending optional challenges don't prevent finishing.
+        let current_exercise_ind = self.app_state.current_exercise_ind();
+        let other_pending = self
+            .app_state
+            .exercises()
+            .iter()
+            .enumerate()
+            .any(|(ind, exercise)| {
+                ind != current_exercise_ind && !exercise.done && !exercise.optional
+            });
+        if !other_pending {
+            return Ok(false);
+        }
+
+        let id = AUTO_ADVANCE_ID.load(Relaxed);
+        let sender = sender.clone();
+        thread::Builder::new()
+            .spawn(move || {
+                thread::sleep(AUTO_ADVANCE_DELAY);
+                let _ = sender.send(WatchEvent::AutoAdvance { id });
+            })
+            .context("Failed to spawn a thread for auto-advancing")?;
+
+        Ok(true)
+    }
+
+    /// Show the warnings (e.g. about the reloaded exercises) with the next rendering.
+    pub fn show_warnings(&mut self, warnings: &[String]) {
+        for warning in warnings {
+            if !self.message.is_empty() {
+                self.message.push('\n');
+            }
+            self.message.push_str(self.messages.warning);
+            self.message.push_str(": ");
+            self.message.push_str(warning);
+        }
+    }
+
+    /// Run the current exercise again even if its file didn't change.
+    pub fn rerun_current_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.run_cache
+            .remove(&self.app_state.current_exercise_ind());
+        self.run_current_exercise(stdout)
+    }
+
+    /// Check only the current exercise again and report whether it passes without advancing.
+    pub fn check_current_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.run_cache
+            .remove(&self.app_state.current_exercise_ind());
+        self.run_current_exercise_with(stdout, true)
+    }
+
     pub fn reset_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
-        clear_terminal(stdout)?;
+        self.clear_screen(stdout)?;
 
-        stdout.write_all(b"Resetting will undo all your changes to the file ")?;
-        stdout.write_all(self.app_state.current_exercise().path.as_bytes())?;
-        stdout.write_all(b"\nReset (y/n)? ")?;
+        write!(
+            stdout,
+            "{} {}\n{} ",
+            self.messages.reset_undoes,
+            self.app_state.current_exercise().path,
+            self.messages.reset_prompt,
+        )?;
         stdout.flush()?;
 
         {
@@ -129,11 +635,21 @@ impl<'a> WatchState<'a> {
                     b'y' | b'Y' => {
                         self.app_state.reset_current_exercise()?;
 
-                        // The file watcher reruns the exercise otherwise.
-                        if self.manual_run {
+                        if self.watcher_paused {
+                            self.render(stdout)?;
+                        } else {
+                            // The reset file might match the cached run which makes the file
+                            // watcher skip it. The event of the watcher is skipped after this run
+                            // instead.
+                            self.run_cache
+                                .remove(&self.app_state.current_exercise_ind());
                             self.run_current_exercise(stdout)?;
                         }
                     }
+                    b'a' | b'A' => {
+                        drop(stdin);
+                        self.reset_all_exercises(stdout)?;
+                    }
                     b'n' | b'N' => self.render(stdout)?,
                     _ => continue,
                 }
@@ -147,18 +663,172 @@ impl<'a> WatchState<'a> {
         Ok(())
     }
 
+    /// Restore the current exercise file from before its last reset and run it again.
+    pub fn undo_reset(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        if self.app_state.undo_current_reset()? {
+            self.run_current_exercise(stdout)?;
+        }
+
+        Ok(())
+    }
+
+    // Requires a second confirmation because all changes to all exercise files are lost.
+    fn reset_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let n_exercises = self.app_state.exercises().len();
+
+        self.clear_screen(stdout)?;
+        write!(
+            stdout,
+            "{} {n_exercises} {}\n{} ",
+            self.messages.reset_all_overwrites,
+            self.messages.reset_all_undoes,
+            self.messages.reset_all_prompt,
+        )?;
+        stdout.flush()?;
+
+        let mut answer = [0];
+        io::stdin()
+            .lock()
+            .read_exact(&mut answer)
+            .context("Failed to read the user's input")?;
+
+        if answer[0] != b'R' {
+            self.render(stdout)?;
+            return Ok(());
+        }
+
+        self.app_state.reset_exercises(0..n_exercises)?;
+        self.run_cache.clear();
+        self.run_current_exercise(stdout)
+    }
+
+    /// Open the current exercise file in the editor from `$VISUAL` or `$EDITOR`.
+    pub fn edit_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        // Ignore any input until the editor is closed.
+        let _input_pause_guard = InputPauseGuard::scoped_pause();
+
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .ok()
+            .filter(|editor| !editor.trim().is_empty());
+        let editor_is_set = editor.is_some();
+        let editor = editor.unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+
+        self.clear_screen(stdout)?;
+        stdout.flush()?;
+
+        // The editor command can contain arguments like `code --wait`.
+        let mut editor_parts = editor.split_whitespace();
+        let mut cmd = Command::new(editor_parts.next().unwrap_or(DEFAULT_EDITOR));
+        cmd.args(editor_parts)
+            .arg(self.app_state.current_exercise().path);
+
+        match cmd.status() {
+            Ok(_) => {
+                // The file watcher reruns the exercise otherwise.
+                if self.manual_run {
+                    self.run_current_exercise(stdout)?;
+                } else {
+                    self.render(stdout)?;
+                }
+            }
+            Err(e) => {
+                writeln!(stdout, "{} `{editor}`: {e}", self.messages.editor_failed)?;
+                if !editor_is_set {
+                    writeln!(stdout, "{}", self.messages.editor_not_set)?;
+                }
+                write!(stdout, "\n{} ", self.messages.press_enter)?;
+                press_enter_prompt(stdout)?;
+                self.render(stdout)?;
+            }
+        }
+
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(())
+    }
+
+    /// Return true if the user wants to quit.
+    /// Asks for a confirmation if there are still pending exercises.
+    pub fn confirm_quit(&mut self, stdout: &mut StdoutLock) -> Result<bool> {
+        let n_pending = self.app_state.n_pending();
+        if !self.confirm_quit || n_pending == 0 {
+            return Ok(true);
+        }
+
+        self.clear_screen(stdout)?;
+
+        if n_pending == 1 {
+            stdout.write_all(self.messages.one_pending.as_bytes())?;
+        } else {
+            write!(
+                stdout,
+                "{} {n_pending} {}",
+                self.messages.there_are_still, self.messages.exercises_pending,
+            )?;
+        }
+        write!(stdout, "\n{} ", self.messages.quit_prompt)?;
+        stdout.flush()?;
+
+        {
+            let mut stdin = io::stdin().lock();
+            let mut answer = [0];
+            loop {
+                stdin
+                    .read_exact(&mut answer)
+                    .context("Failed to read the user's input")?;
+
+                match answer[0] {
+                    b'y' | b'Y' => return Ok(true),
+                    b'n' | b'N' => break,
+                    _ => continue,
+                }
+            }
+        }
+
+        self.render(stdout)?;
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(false)
+    }
+
     pub fn handle_file_change(
         &mut self,
         exercise_ind: usize,
         stdout: &mut StdoutLock,
     ) -> Result<()> {
-        if self.app_state.current_exercise_ind() != exercise_ind {
+        if self.app_state.current_exercise_ind() != exercise_ind || self.watcher_paused {
             return Ok(());
         }
 
+        // Skip saves without changes (e.g. `touch`) to avoid redrawing for nothing.
+        // The file is only hashed if its modification time changed.
+        if let Some(cached_run) = self.run_cache.get_mut(&exercise_ind) {
+            let path = self.app_state.current_exercise().path;
+            let mtime = file_mtime(path);
+            if mtime.is_some() && mtime == cached_run.mtime {
+                return Ok(());
+            }
+
+            if file_hash(path) == Some(cached_run.source_hash) {
+                cached_run.mtime = mtime;
+                return Ok(());
+            }
+        }
+
         self.run_current_exercise(stdout)
     }
 
+    /// Handled like a change of the current exercise file. Unchanged files aren't run again.
+    pub fn handle_interval(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        // Missing files aren't cached. Don't redraw until the file is restored.
+        if self.file_missing && !Path::new(self.app_state.current_exercise().path).exists() {
+            return Ok(());
+        }
+
+        self.handle_file_change(self.app_state.current_exercise_ind(), stdout)
+    }
+
     /// Move on to the next exercise if the current one is done.
     pub fn next_exercise(&mut self, stdout: &mut StdoutLock) -> Result<ExercisesProgress> {
         match self.done_status {
@@ -166,115 +836,1053 @@ impl<'a> WatchState<'a> {
             DoneStatus::Pending => return Ok(ExercisesProgress::CurrentPending),
         }
 
-        self.app_state.done_current_exercise::<true>(stdout)
+        self.record_time_spent()?;
+        let progress = self.app_state.done_current_exercise::<true>(stdout)?;
+        if let ExercisesProgress::NewPending = progress {
+            self.send_event(ProgressEvent::Advanced {
+                exercise: self.app_state.current_exercise().name,
+            });
+        }
+
+        Ok(progress)
     }
 
-    fn show_prompt(&self, stdout: &mut StdoutLock) -> io::Result<()> {
-        if self.done_status != DoneStatus::Pending {
-            stdout.queue(SetAttribute(Attribute::Bold))?;
-            stdout.write_all(b"n")?;
-            stdout.queue(ResetColor)?;
-            stdout.write_all(b":")?;
-            stdout.queue(SetAttribute(Attribute::Underlined))?;
-            stdout.write_all(b"next")?;
-            stdout.queue(ResetColor)?;
-            stdout.write_all(b" / ")?;
-        }
-
-        let mut show_key = |key, postfix| {
-            stdout.queue(SetAttribute(Attribute::Bold))?;
-            stdout.write_all(&[key])?;
-            stdout.queue(ResetColor)?;
-            stdout.write_all(postfix)
+    /// Go back to the previous exercise independent of the done status of the current one.
+    /// The previous exercise isn't run until its file changes.
+    pub fn prev_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let Some(prev_exercise_ind) = self.app_state.current_exercise_ind().checked_sub(1) else {
+            return Ok(());
         };
 
-        if self.manual_run {
-            show_key(b'r', b":run / ")?;
-        }
-
-        if !self.show_hint {
-            show_key(b'h', b":hint / ")?;
-        }
+        self.show_exercise(prev_exercise_ind, stdout)
+    }
 
-        show_key(b'l', b":list / ")?;
-        show_key(b'c', b":check all / ")?;
-        show_key(b'x', b":reset / ")?;
-        show_key(b'q', b":quit ? ")?;
+    /// Return to the exercise before the last jump.
+    pub fn go_back(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let Some(exercise_ind) = self.history.pop() else {
+            return Ok(());
+        };
 
-        stdout.flush()
+        self.show_exercise(exercise_ind, stdout)
     }
 
-    pub fn render(&self, stdout: &mut StdoutLock) -> io::Result<()> {
-        // Prevent having the first line shifted if clearing wasn't successful.
-        stdout.write_all(b"\n")?;
-        clear_terminal(stdout)?;
+    // The first failure of the last check of all exercises after the current exercise.
+    // Wraps around. Exercises that are done by now are skipped.
+    fn next_failure_ind(&self) -> Option<usize> {
+        let current_exercise_ind = self.app_state.current_exercise_ind();
+        let exercises = self.app_state.exercises();
+        let mut failures = self.failures.iter().copied().filter(|&ind| {
+            ind != current_exercise_ind && !exercises[ind].done && !self.app_state.is_locked(ind)
+        });
 
-        stdout.write_all(&self.output)?;
+        failures
+            .clone()
+            .find(|&ind| ind > current_exercise_ind)
+            .or_else(|| failures.next())
+    }
 
-        if self.show_hint {
-            stdout
-                .queue(SetAttributes(HEADING_ATTRIBUTES))?
-                .queue(SetForegroundColor(Color::Cyan))?;
-            stdout.write_all(b"Hint")?;
-            stdout.queue(ResetColor)?;
-            stdout.write_all(b"\n")?;
+    /// Move on to the next exercise that failed in the last check of all exercises and run it.
+    pub fn next_failure(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let Some(exercise_ind) = self.next_failure_ind() else {
+            return Ok(());
+        };
+
+        self.record_time_spent()?;
+        self.history.push(self.app_state.current_exercise_ind());
+        self.app_state.set_current_exercise_ind(exercise_ind)?;
+        self.run_current_exercise(stdout)
+    }
 
-            stdout.write_all(self.app_state.current_exercise().hint.as_bytes())?;
-            stdout.write_all(b"\n\n")?;
+    // Switch to the exercise without running it.
+    fn show_exercise(&mut self, exercise_ind: usize, stdout: &mut StdoutLock) -> Result<()> {
+        if self.app_state.is_locked(exercise_ind) {
+            self.message.push_str(&format!(
+                "{} `{}` {} {}",
+                self.messages.the_exercise,
+                self.app_state.exercises()[exercise_ind].name,
+                self.messages.locked_until,
+                quoted_list(&self.app_state.pending_prerequisites(exercise_ind)),
+            ));
+            self.rerender(stdout)?;
+            self.message.clear();
+            return Ok(());
         }
 
+        self.record_time_spent()?;
+        self.app_state.set_current_exercise_ind(exercise_ind)?;
+
+        self.output.clear();
+        self.warnings.clear();
+        self.diagnostics.clear();
+        self.n_shown_hints = 0;
+        self.diff = None;
+        self.peek = None;
+        self.done_status = if !self.app_state.current_exercise().done {
+            DoneStatus::Pending
+        } else if let Some(solution_path) = self.app_state.current_solution_path()? {
+            DoneStatus::DoneWithSolution(solution_path)
+        } else {
+            DoneStatus::DoneWithoutSolution
+        };
+
+        self.render(stdout)?;
+        Ok(())
+    }
+
+    /// Move on to the next pending exercise without marking the current one as done.
+    /// The skipped exercise is shown again after all other pending exercises.
+    pub fn skip_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
         if self.done_status != DoneStatus::Pending {
-            stdout
-                .queue(SetAttribute(Attribute::Bold))?
-                .queue(SetForegroundColor(Color::Green))?;
-            stdout.write_all("Exercise done ✓".as_bytes())?;
-            stdout.queue(ResetColor)?;
-            stdout.write_all(b"\n")?;
+            return Ok(());
+        }
 
-            if let DoneStatus::DoneWithSolution(solution_path) = &self.done_status {
-                solution_link_line(stdout, solution_path)?;
+        self.record_time_spent()?;
+        let exercise_ind = self.app_state.current_exercise_ind();
+        match self.app_state.skip_current_exercise()? {
+            ExercisesProgress::NewPending => {
+                self.history.push(exercise_ind);
+                self.run_current_exercise(stdout)
             }
-
-            stdout.write_all(
-                "When done experimenting, enter `n` to move on to the next exercise 🦀\n\n"
-                    .as_bytes(),
-            )?;
+            // There is no other pending exercise to skip to.
+            ExercisesProgress::CurrentPending | ExercisesProgress::AllDone => Ok(()),
         }
+    }
 
-        progress_bar(
-            stdout,
-            self.app_state.n_done(),
-            self.app_state.exercises().len() as u16,
-            self.term_width,
-        )?;
+    fn show_prompt(&self, frame: &mut Vec<u8>) -> io::Result<()> {
+        let theme = self.app_state.theme();
+        let messages = self.messages;
 
-        stdout.write_all(b"\nCurrent exercise: ")?;
-        self.app_state
-            .current_exercise()
-            .terminal_file_link(stdout)?;
-        stdout.write_all(b"\n\n")?;
+        if self.done_status != DoneStatus::Pending {
+            theme.queue_style(frame, SetAttribute(Attribute::Bold))?;
+            write!(frame, "{}", self.keys.next)?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b":")?;
+            theme.queue_style(frame, SetAttribute(Attribute::Underlined))?;
+            frame.write_all(messages.next.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b" / ")?;
+        }
 
-        self.show_prompt(stdout)?;
+        let mut show_key = |key: char, label: &str| {
+            theme.queue_style(frame, SetAttribute(Attribute::Bold))?;
+            write!(frame, "{key}")?;
+            theme.queue_style(frame, ResetColor)?;
+            write!(frame, ":{label} / ")
+        };
 
-        Ok(())
-    }
+        if self.app_state.current_exercise_ind() > 0 {
+            show_key(self.keys.prev, messages.prev)?;
+        }
 
-    pub fn show_hint(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
-        if !self.show_hint {
-            self.show_hint = true;
-            self.render(stdout)?;
+        if !self.history.is_empty() {
+            show_key(self.keys.back, messages.back)?;
         }
 
-        Ok(())
-    }
+        if let DoneStatus::DoneWithSolution(_) = self.done_status {
+            show_key(self.keys.copy_solution, messages.copy_solution)?;
+            if self.diff.is_some() {
+                show_key(self.keys.diff, messages.hide_diff)?;
+            } else {
+                show_key(self.keys.diff, messages.diff)?;
+            }
+        }
 
-    pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<ExercisesProgress> {
-        // Ignore any input until checking all exercises is done.
-        let _input_pause_guard = InputPauseGuard::scoped_pause();
+        if self.can_peek() {
+            if self.peek.is_some() {
+                show_key(self.keys.peek, messages.hide_peek)?;
+            } else {
+                show_key(self.keys.peek, messages.peek)?;
+            }
+        }
+
+        if self.done_status == DoneStatus::Pending {
+            show_key(self.keys.skip, messages.skip)?;
+        }
+
+        show_key(self.keys.run, messages.run)?;
+
+        // The disabled hints aren't even mentioned.
+        if self.hints_hidden {
+            if !self.hints_disabled {
+                show_key(self.keys.toggle_hints, messages.show_hints)?;
+            }
+        } else {
+            if self.n_shown_hints < self.app_state.current_exercise().hints.len() {
+                if self.n_shown_hints > 0 {
+                    show_key(self.keys.hint, messages.next_hint)?;
+                } else if self.app_state.current_exercise().hint_viewed {
+                    show_key(self.keys.hint, messages.hint_previously_viewed)?;
+                } else {
+                    show_key(self.keys.hint, messages.hint_cmd)?;
+                }
+            }
+            if !self.app_state.current_exercise().hints.is_empty() {
+                show_key(self.keys.hint_pager, messages.hint_pager)?;
+            }
+            show_key(self.keys.toggle_hints, messages.hide_hints)?;
+        }
+
+        if self.app_state.current_exercise().bookmarked {
+            show_key(self.keys.bookmark, messages.remove_bookmark)?;
+        } else {
+            show_key(self.keys.bookmark, messages.bookmark)?;
+        }
+        if self.show_groups {
+            show_key(self.keys.groups, messages.hide_groups)?;
+        } else {
+            show_key(self.keys.groups, messages.groups)?;
+        }
+        show_key(self.keys.stats, messages.stats)?;
+        show_key(self.keys.note, messages.note)?;
+        show_key(self.keys.report, messages.report)?;
+        if self.wrap_output {
+            show_key(self.keys.wrap, messages.unwrap)?;
+        } else {
+            show_key(self.keys.wrap, messages.wrap)?;
+        }
+        // There is no file watcher in the manual run mode.
+        if !self.manual_run {
+            if self.watcher_paused {
+                show_key(self.keys.pause_watcher, messages.resume_watcher)?;
+            } else {
+                show_key(self.keys.pause_watcher, messages.pause_watcher)?;
+            }
+        }
+        show_key(self.keys.redraw, messages.redraw)?;
+        show_key(self.keys.view, messages.view)?;
+        show_key(self.keys.edit, messages.edit)?;
+        show_key(self.keys.list, messages.list)?;
+        show_key(self.keys.check, messages.check)?;
+        show_key(self.keys.check_all, messages.check_all)?;
+        if self.next_failure_ind().is_some() {
+            show_key(self.keys.next_failure, messages.next_failure)?;
+        }
+        show_key(self.keys.reset, messages.reset)?;
+        if self.app_state.can_undo_current_reset() {
+            show_key(self.keys.undo, messages.undo_reset)?;
+        }
+        if !self.app_state.official_exercises() {
+            show_key(self.keys.reload, messages.reload)?;
+        }
+        theme.queue_style(frame, SetAttribute(Attribute::Bold))?;
+        write!(frame, "{}", self.keys.quit)?;
+        theme.queue_style(frame, ResetColor)?;
+        write!(frame, ":{} ? ", messages.quit)
+    }
+
+    // The name of the current exercise, whether it is done and the progress in one line.
+    // The line is overwritten by the next render.
+    fn render_status_line(&self, stdout: &mut StdoutLock) -> io::Result<()> {
+        let theme = self.app_state.theme();
+        stdout.write_all(b"\r")?;
+        stdout.queue(Clear(ClearType::CurrentLine))?;
+
+        // The width is 0 if the terminal doesn't report its size.
+        let max_len = match self.term_width {
+            0 => usize::MAX,
+            width => usize::from(width),
+        };
+        let mut writer = MaxLenWriter::new(stdout, max_len);
+        writer.write_str(self.app_state.current_exercise().name)?;
+        writer.write_ascii(b" ")?;
+        if self.done_status == DoneStatus::Pending {
+            writer.write_str(if theme.a11y() { "pending" } else { "✗" })?;
+        } else {
+            theme.queue_style(writer.stdout, SetForegroundColor(theme.done))?;
+            writer.write_str(if theme.a11y() {
+                "done"
+            } else {
+                theme.completion.glyph
+            })?;
+        }
+        theme.queue_style(writer.stdout, ResetColor)?;
+        let (n_core_done, n_core) = self.app_state.core_progress();
+        writer.write_str(&format!(" {n_core_done}/{n_core} done"))?;
+
+        stdout.flush()
+    }
+
+    // Clear the terminal to show something other than a frame.
+    fn clear_screen(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        self.last_frame.clear();
+        clear_terminal(stdout)
+    }
+
+    pub fn render(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        if self.status_line {
+            return self.render_status_line(stdout);
+        }
+
+        let mut frame = Vec::with_capacity(self.last_frame.len());
+        self.render_frame(&mut frame)?;
+
+        // Prevent having the first line shifted if clearing wasn't successful.
+        stdout.write_all(b"\n")?;
+        clear_terminal(stdout)?;
+        stdout.write_all(&frame)?;
+        stdout.flush()?;
+        self.last_frame = frame;
+
+        Ok(())
+    }
+
+    // Render again after a change that doesn't affect most of the screen like a toggle.
+    // In the low-bandwidth mode, only the lines starting with the first changed one are
+    // redrawn if the last frame is still on the screen.
+    pub fn rerender(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        if !self.low_bandwidth || self.status_line || self.last_frame.is_empty() {
+            return self.render(stdout);
+        }
+
+        let mut frame = Vec::with_capacity(self.last_frame.len());
+        self.render_frame(&mut frame)?;
+
+        // A frame that scrolled the terminal doesn't start at the first row anymore.
+        let Ok((_, height)) = terminal::size() else {
+            return self.render(stdout);
+        };
+        let width = usize::from(self.term_width);
+        if width == 0 || term::n_rows(&self.last_frame, width) >= usize::from(height) {
+            return self.render(stdout);
+        }
+
+        // The start of the first line that differs from the last frame.
+        let common_len = frame
+            .iter()
+            .zip(&self.last_frame)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let line_start = frame[..common_len]
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |ind| ind + 1);
+        if line_start == frame.len() && frame.len() == self.last_frame.len() {
+            return Ok(());
+        }
+        let row = term::n_rows(&frame[..line_start], width);
+
+        stdout.queue(MoveTo(0, u16::try_from(row).unwrap_or(u16::MAX)))?;
+        stdout.queue(Clear(ClearType::FromCursorDown))?;
+        stdout.write_all(&frame[line_start..])?;
+        stdout.flush()?;
+        self.last_frame = frame;
+
+        Ok(())
+    }
+
+    fn render_frame(&self, frame: &mut Vec<u8>) -> io::Result<()> {
+        let mut wrapped = Vec::new();
+        let mut write_output = |frame: &mut Vec<u8>, output: &[u8]| {
+            if self.wrap_output {
+                wrap_output(output, usize::from(self.term_width), &mut wrapped);
+                frame.write_all(&wrapped)
+            } else {
+                frame.write_all(output)
+            }
+        };
+
+        // Falls back to the stacked layout if the hints would be squeezed.
+        let two_columns = self.two_columns
+            && self.term_width >= TWO_COLUMNS_MIN_WIDTH
+            && self.n_shown_hints > 0
+            && !self.output.is_empty();
+        if two_columns {
+            let mut hints = Vec::new();
+            for hint_ind in 0..self.n_shown_hints {
+                self.render_hint(&mut hints, hint_ind)?;
+            }
+            side_by_side(&self.output, &hints, usize::from(self.term_width), frame);
+        } else {
+            write_output(frame, &self.output)?;
+        }
+
+        let theme = self.app_state.theme();
+        if self.file_missing {
+            theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+            theme.queue_style(frame, SetForegroundColor(theme.warning))?;
+            frame.write_all(self.messages.file_missing.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            writeln!(
+                frame,
+                ": {}
+{} `{}`
+",
+                self.app_state.current_exercise().path,
+                self.messages.reset_to_restore,
+                self.keys.reset,
+            )?;
+        }
+
+        if !self.warnings.is_empty() {
+            theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+            theme.queue_style(frame, SetForegroundColor(theme.warning))?;
+            frame.write_all(self.messages.warnings.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n")?;
+            write_output(frame, &self.warnings)?;
+        }
+
+        // Only augments the compiler output.
+        let error_notes = if self.error_notes {
+            error_notes(&self.output)
+        } else {
+            Vec::new()
+        };
+        if !error_notes.is_empty() {
+            theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+            theme.queue_style(frame, SetForegroundColor(theme.hint))?;
+            frame.write_all(self.messages.about_the_errors.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n")?;
+            for (code, note) in error_notes {
+                writeln!(frame, "- {code}: {note}")?;
+            }
+            frame.write_all(b"\n")?;
+        }
+
+        if !self.diagnostics.is_empty() {
+            theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+            theme.queue_style(frame, SetForegroundColor(theme.hint))?;
+            frame.write_all(self.messages.diagnostics.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n")?;
+            for diagnostic in &self.diagnostics {
+                writeln!(
+                    frame,
+                    "{}:{} {}: {}",
+                    diagnostic.line, diagnostic.col, diagnostic.severity, diagnostic.message,
+                )?;
+                if !diagnostic.source_line.is_empty() {
+                    theme.queue_style(frame, SetAttribute(Attribute::Dim))?;
+                    write!(frame, "  | {}", diagnostic.source_line)?;
+                    theme.queue_style(frame, ResetColor)?;
+                    frame.write_all(b"\n")?;
+                }
+            }
+            frame.write_all(b"\n")?;
+        }
+
+        let notes = &self.app_state.current_exercise().notes;
+        if !notes.is_empty() {
+            theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+            frame.write_all(self.messages.notes.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n")?;
+            for note in notes {
+                frame.write_all(b"- ")?;
+                frame.write_all(note.as_bytes())?;
+                frame.write_all(b"\n")?;
+            }
+            frame.write_all(b"\n")?;
+        }
+
+        if !two_columns {
+            for hint_ind in 0..self.n_shown_hints {
+                self.render_hint(frame, hint_ind)?;
+            }
+        }
+
+        if let Some(peek) = &self.peek {
+            theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+            theme.queue_style(frame, SetForegroundColor(theme.hint))?;
+            frame.write_all(self.messages.solution_peek.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n")?;
+            write_output(frame, peek.as_bytes())?;
+            frame.write_all(b"\n")?;
+        }
+
+        if self.done_status != DoneStatus::Pending {
+            theme.queue_style(frame, SetAttribute(Attribute::Bold))?;
+            theme.queue_style(frame, SetForegroundColor(theme.done))?;
+            frame.write_all(self.messages.exercise_done.as_bytes())?;
+            if !theme.a11y() {
+                write!(frame, " {}", theme.completion.glyph)?;
+            }
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n")?;
+
+            if let Some(n) = self.fixed_after {
+                theme.queue_style(frame, SetForegroundColor(theme.done))?;
+                write!(
+                    frame,
+                    "{} {n} {}",
+                    self.messages.fixed_after,
+                    if n == 1 {
+                        self.messages.failed_run
+                    } else {
+                        self.messages.failed_runs
+                    },
+                )?;
+                if !theme.a11y() {
+                    frame.write_all(" ✨".as_bytes())?;
+                }
+                theme.queue_style(frame, ResetColor)?;
+                frame.write_all(b"\n")?;
+            }
+
+            let time_spent = self.app_state.current_exercise().time_spent;
+            if !time_spent.is_zero() {
+                write!(frame, "{} ", self.messages.completed_in)?;
+                write_duration(frame, time_spent)?;
+                frame.write_all(b"\n")?;
+            }
+
+            if let DoneStatus::DoneWithSolution(solution_path) = &self.done_status {
+                solution_link_line(frame, solution_path, theme)?;
+            }
+
+            if let Some(diff) = &self.diff {
+                frame.write_all(b"\n")?;
+                write_output(frame, diff)?;
+                frame.write_all(b"\n")?;
+            }
+
+            write!(frame, "{} `{}` ", self.messages.when_done, self.keys.next)?;
+            if let Some(move_on) = theme.completion.move_on {
+                frame.write_all(move_on.as_bytes())?;
+            } else {
+                frame.write_all(self.messages.to_move_on.as_bytes())?;
+                if !theme.a11y() && !theme.completion.emoji.is_empty() {
+                    write!(frame, " {}", theme.completion.emoji)?;
+                }
+            }
+            frame.write_all(b"\n\n")?;
+        }
+
+        self.render_footer(frame)
+    }
+
+    fn render_hint(&self, frame: &mut Vec<u8>, hint_ind: usize) -> io::Result<()> {
+        let theme = self.app_state.theme();
+        let hints = self.app_state.current_exercise().hints;
+
+        theme.queue_style(frame, SetAttributes(HEADING_ATTRIBUTES))?;
+        theme.queue_style(frame, SetForegroundColor(theme.hint))?;
+        if hints.len() == 1 {
+            frame.write_all(self.messages.hint.as_bytes())?;
+        } else {
+            write!(
+                frame,
+                "{} {}/{}",
+                self.messages.hint,
+                hint_ind + 1,
+                hints.len(),
+            )?;
+        }
+        theme.queue_style(frame, ResetColor)?;
+        frame.write_all(b"\n")?;
+
+        frame.write_all(hints[hint_ind].as_bytes())?;
+        frame.write_all(b"\n\n")
+    }
+
+    // The progress bar, the current exercise and the prompt.
+    fn render_footer(&self, frame: &mut Vec<u8>) -> io::Result<()> {
+        let theme = self.app_state.theme();
+
+        let (n_core_done, n_core) = self.app_state.core_progress();
+        progress_bar(frame, n_core_done, n_core, self.term_width, theme)?;
+        frame.write_all(b"\n")?;
+
+        let (n_challenges_done, n_challenges) = self.app_state.challenge_progress();
+        if n_challenges > 0 {
+            writeln!(
+                frame,
+                "{}: {n_core_done}/{n_core}, {}: {n_challenges_done}/{n_challenges}",
+                self.messages.core, self.messages.challenges,
+            )?;
+        }
+
+        if self.show_groups {
+            self.render_groups(frame)?;
+        }
+
+        write!(frame, "{}: ", self.messages.current_exercise)?;
+        self.app_state
+            .current_exercise()
+            .terminal_file_link(frame, theme)?;
+        if self.app_state.has_difficulties() {
+            let difficulty = self
+                .app_state
+                .current_exercise()
+                .difficulty
+                .map_or("unrated", Difficulty::as_str);
+            write!(frame, " [{difficulty}]")?;
+        }
+        let n_hints_shown = self.app_state.current_exercise().n_hints_shown;
+        if n_hints_shown > 0 {
+            write!(frame, " ({}: {n_hints_shown})", self.messages.hints_used)?;
+        }
+        frame.write_all(b"\n\n")?;
+
+        if self.watcher_paused {
+            theme.queue_style(frame, SetForegroundColor(theme.warning))?;
+            frame.write_all(self.messages.watcher_paused.as_bytes())?;
+            theme.queue_style(frame, ResetColor)?;
+            frame.write_all(b"\n\n")?;
+        }
+
+        if !self.message.is_empty() {
+            frame.write_all(self.message.as_bytes())?;
+            frame.write_all(b"\n\n")?;
+        }
+
+        // The prompt is always shown when the exercise is done to not miss the key for `next`.
+        if !self.focus_mode || self.prompt_revealed || self.done_status != DoneStatus::Pending {
+            self.show_prompt(frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Show the prompt in the focus mode after a key press.
+    /// Returns `true` if the prompt was hidden before.
+    pub fn reveal_prompt(&mut self) -> bool {
+        let hidden = self.focus_mode && !self.prompt_revealed;
+        self.prompt_revealed = true;
+        hidden
+    }
+
+    // Compact lines like `variables 4/6  functions 5/5` wrapped at the terminal width.
+    fn render_groups(&self, frame: &mut Vec<u8>) -> io::Result<()> {
+        let theme = self.app_state.theme();
+        let mut line_len = 0;
+
+        for group in self.app_state.group_progresses() {
+            let entry = format!("{} {}/{}", group.name, group.n_done, group.n_exercises);
+            let entry_len = entry.chars().count();
+
+            if line_len > 0 {
+                if line_len + 2 + entry_len > usize::from(self.term_width) {
+                    frame.write_all(b"\n")?;
+                    line_len = 0;
+                } else {
+                    frame.write_all(b"  ")?;
+                    line_len += 2;
+                }
+            }
+
+            if group.n_done == group.n_exercises {
+                theme.queue_style(frame, SetForegroundColor(theme.done))?;
+                frame.write_all(entry.as_bytes())?;
+                theme.queue_style(frame, ResetColor)?;
+            } else {
+                frame.write_all(entry.as_bytes())?;
+            }
+            line_len += entry_len;
+        }
+
+        frame.write_all(b"\n")
+    }
+
+    /// Show the completion statistics until any key is pressed.
+    pub fn show_stats(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let stats = self.app_state.stats();
+        let theme = self.app_state.theme();
+        let messages = self.messages;
+
+        clear_terminal(stdout)?;
+
+        theme.queue_style(stdout, SetAttributes(HEADING_ATTRIBUTES))?;
+        stdout.write_all(messages.stats_heading.as_bytes())?;
+        theme.queue_style(stdout, ResetColor)?;
+        stdout.write_all(b"\n\n")?;
+
+        // Align the values of all labels.
+        let labels = [
+            messages.stats_done,
+            messages.stats_streak,
+            messages.stats_hints_viewed,
+            messages.stats_hints_shown,
+            messages.stats_peeks,
+            messages.stats_time_spent,
+        ];
+        let width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            + 2;
+        let label = |label: &str| format!("{label}:");
+
+        writeln!(
+            stdout,
+            "{:width$}{}/{} ({:.1}%)",
+            label(messages.stats_done),
+            stats.n_done,
+            stats.n_exercises,
+            f32::from(stats.n_done) * 100.0 / f32::from(stats.n_exercises.max(1)),
+        )?;
+        writeln!(
+            stdout,
+            "{:width$}{}",
+            label(messages.stats_streak),
+            stats.streak
+        )?;
+        writeln!(
+            stdout,
+            "{:width$}{}",
+            label(messages.stats_hints_viewed),
+            stats.n_hints_viewed,
+        )?;
+        writeln!(
+            stdout,
+            "{:width$}{}",
+            label(messages.stats_hints_shown),
+            stats.n_hints_shown,
+        )?;
+        writeln!(
+            stdout,
+            "{:width$}{}",
+            label(messages.stats_peeks),
+            stats.n_solutions_peeked,
+        )?;
+        if !stats.time_spent.is_zero() {
+            write!(stdout, "{:width$}", label(messages.stats_time_spent))?;
+            write_duration(stdout, stats.time_spent)?;
+            stdout.write_all(b"\n")?;
+        }
+
+        // The exercises done for the first time per day.
+        write!(
+            stdout,
+            "{} {TREND_DAYS} {}: ",
+            messages.stats_last, messages.stats_days,
+        )?;
+        match &stats.daily_done {
+            Some(daily_done) => sparkline(stdout, daily_done, theme.progress_done, theme)?,
+            None => stdout.write_all(messages.not_enough_data.as_bytes())?,
+        }
+        stdout.write_all(b"\n")?;
+
+        write!(stdout, "\n{} ", messages.press_any_key)?;
+        stdout.flush()?;
+
+        let mut key = [0];
+        io::stdin()
+            .lock()
+            .read_exact(&mut key)
+            .context("Failed to read the user's input")?;
+
+        self.render(stdout)?;
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(())
+    }
+
+    /// Show the source of the current exercise until a key other than a scroll key is pressed.
+    pub fn view_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let _input_pause_guard = InputPauseGuard::scoped_pause();
+
+        let path = self.app_state.current_exercise().path;
+        if self.file_missing {
+            self.render(stdout)?;
+            self.terminal_event_unpause_sender.send(())?;
+            return Ok(());
+        }
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the exercise file {path}"))?;
+        pager(stdout, &source, path, true, self.app_state.theme())?;
+
+        self.render(stdout)?;
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(())
+    }
+
+    /// Read a note for the current exercise from the user and save it.
+    /// The special note `-` removes all notes of the current exercise.
+    pub fn add_note(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        write!(stdout, "\n\n{} ", self.messages.note_prompt)?;
+        if let Some(note) = read_line(stdout)? {
+            if note.trim() == "-" {
+                self.app_state.clear_current_notes()?;
+            } else {
+                self.app_state.add_current_note(&note)?;
+            }
+        }
+
+        self.render(stdout)?;
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(())
+    }
+
+    /// Read a description of a problem with the current exercise (e.g. a solution that doesn't
+    /// compile or a wrong hint) and append it to the reports file.
+    pub fn report_exercise(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        write!(stdout, "\n\n{} ", self.messages.report_prompt)?;
+        if let Some(report) = read_line(stdout)?.filter(|report| !report.trim().is_empty()) {
+            self.app_state.report_current_exercise(&report)?;
+            self.message.push_str(self.messages.report_saved);
+            self.message.push_str(" `");
+            self.message.push_str(
+                &term::canonicalize(REPORTS_FILE_NAME)
+                    .unwrap_or_else(|| REPORTS_FILE_NAME.to_string()),
+            );
+            self.message.push('`');
+        }
+
+        self.render(stdout)?;
+        self.message.clear();
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(())
+    }
+
+    // Whether the `peek` command is available for the current exercise.
+    fn can_peek(&self) -> bool {
+        self.peek_lines > 0
+            && match self.done_status {
+                DoneStatus::DoneWithSolution(_) => true,
+                DoneStatus::DoneWithoutSolution => false,
+                DoneStatus::Pending => self.peek_while_pending,
+            }
+    }
+
+    /// Show or hide the first lines of the solution as a last resort hint.
+    pub fn toggle_peek(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        if !self.can_peek() {
+            return Ok(());
+        }
+
+        if self.peek.take().is_none() {
+            let Some(solution_path) = self.app_state.current_solution_path()? else {
+                self.message.push_str(self.messages.no_solution);
+                self.rerender(stdout)?;
+                self.message.clear();
+                return Ok(());
+            };
+
+            let solution = fs::read_to_string(&solution_path)
+                .with_context(|| format!("Failed to read the solution file {solution_path}"))?;
+            let mut peek = String::with_capacity(80 * self.peek_lines);
+            for line in solution.lines().take(self.peek_lines) {
+                peek.push_str(line);
+                peek.push('\n');
+            }
+
+            self.peek = Some(peek);
+            self.app_state.set_current_solution_peeked()?;
+        }
+
+        self.rerender(stdout)?;
+
+        Ok(())
+    }
+
+    /// Show or hide the line diff between the exercise file and its solution.
+    /// Only available if the exercise is done and has a solution.
+    pub fn toggle_diff(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let DoneStatus::DoneWithSolution(solution_path) = &self.done_status else {
+            return Ok(());
+        };
+
+        if self.diff.take().is_none() {
+            let exercise_path = self.app_state.current_exercise().path;
+            let exercise = fs::read_to_string(exercise_path)
+                .with_context(|| format!("Failed to read the exercise file {exercise_path}"))?;
+            let solution = fs::read_to_string(solution_path)
+                .with_context(|| format!("Failed to read the solution file {solution_path}"))?;
+
+            let theme = self.app_state.theme();
+            let mut diff = Vec::with_capacity(exercise.len() + solution.len());
+            theme.queue_style(&mut diff, SetAttributes(HEADING_ATTRIBUTES))?;
+            diff.extend_from_slice(self.messages.diff_heading.as_bytes());
+            theme.queue_style(&mut diff, ResetColor)?;
+            writeln!(diff, " {}", self.messages.diff_legend)?;
+
+            for line in line_diff(&exercise, &solution) {
+                match line {
+                    DiffLine::Unchanged(line) => {
+                        diff.extend_from_slice(b"  ");
+                        diff.extend_from_slice(line.as_bytes());
+                    }
+                    DiffLine::Removed(line) => {
+                        theme.queue_style(&mut diff, SetForegroundColor(theme.diff_removed))?;
+                        diff.extend_from_slice(b"- ");
+                        diff.extend_from_slice(line.as_bytes());
+                        theme.queue_style(&mut diff, ResetColor)?;
+                    }
+                    DiffLine::Added(line) => {
+                        theme.queue_style(&mut diff, SetForegroundColor(theme.diff_added))?;
+                        diff.extend_from_slice(b"+ ");
+                        diff.extend_from_slice(line.as_bytes());
+                        theme.queue_style(&mut diff, ResetColor)?;
+                    }
+                }
+                diff.push(b'\n');
+            }
+
+            self.diff = Some(diff);
+        }
+
+        self.rerender(stdout)?;
+
+        Ok(())
+    }
+
+    /// Copy the absolute path of the solution to the clipboard if the exercise is done.
+    pub fn copy_solution(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        let DoneStatus::DoneWithSolution(solution_path) = &self.done_status else {
+            return Ok(());
+        };
+
+        let path = term::canonicalize(solution_path).unwrap_or_else(|| solution_path.clone());
+        if copy_to_clipboard(&path) {
+            self.message.push_str(self.messages.solution_path_copied);
+        } else {
+            self.message.push_str(&format!(
+                "{} {}. {} {path}",
+                self.messages.no_clipboard,
+                quoted_list(&clipboard_command_names()),
+                self.messages.solution_path_is,
+            ));
+        }
+
+        self.rerender(stdout)?;
+        self.message.clear();
+
+        Ok(())
+    }
+
+    pub fn toggle_bookmark(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.app_state.toggle_current_bookmark()?;
+        self.rerender(stdout)?;
+        Ok(())
+    }
+
+    pub fn toggle_wrap(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        self.wrap_output = !self.wrap_output;
+        self.rerender(stdout)
+    }
+
+    /// Pause or resume running the exercise on file changes.
+    /// The exercise is run once when resuming to catch up with the changes.
+    pub fn toggle_watcher_pause(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        if self.manual_run {
+            return Ok(());
+        }
+
+        self.watcher_paused = !self.watcher_paused;
+        if self.watcher_paused {
+            self.rerender(stdout)?;
+        } else {
+            self.run_current_exercise(stdout)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn toggle_groups(&mut self, stdout: &mut StdoutLock) -> io::Result<()> {
+        self.show_groups = !self.show_groups;
+        self.rerender(stdout)
+    }
+
+    // Reveal the next hint of the current exercise.
+    pub fn show_hint(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        if !self.hints_hidden && self.n_shown_hints < self.app_state.current_exercise().hints.len()
+        {
+            self.n_shown_hints += 1;
+            self.app_state.set_current_hint_viewed()?;
+
+            // The hint is beside the output.
+            if self.two_columns && self.term_width >= TWO_COLUMNS_MIN_WIDTH {
+                self.rerender(stdout)?;
+                return Ok(());
+            }
 
-        if let Some(first_pending_exercise_ind) = self.app_state.check_all_exercises(stdout)? {
+            // Append the hint instead of clearing the terminal to keep the output
+            // that is already shown in the scrollback.
+            let mut appended = b"\n\n".to_vec();
+            self.render_hint(&mut appended, self.n_shown_hints - 1)?;
+            self.render_footer(&mut appended)?;
+            stdout.write_all(&appended)?;
+            stdout.flush()?;
+            // The screen still matches the last frame with the appended lines.
+            if !self.last_frame.is_empty() {
+                self.last_frame.extend_from_slice(&appended);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Show the hints in a pager to keep the output intact. Opening it reveals the next hint
+    /// like the `hint` command does.
+    pub fn show_hint_pager(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        let _input_pause_guard = InputPauseGuard::scoped_pause();
+
+        let hints = self.app_state.current_exercise().hints;
+        if !self.hints_hidden && !hints.is_empty() {
+            if self.n_shown_hints < hints.len() {
+                self.n_shown_hints += 1;
+                self.app_state.set_current_hint_viewed()?;
+            }
+
+            let mut text = String::new();
+            for (hint_ind, hint) in hints.iter().take(self.n_shown_hints).enumerate() {
+                if hint_ind > 0 {
+                    text.push('\n');
+                }
+                if hints.len() > 1 {
+                    text.push_str(&format!(
+                        "{} {}/{}\n",
+                        self.messages.hint,
+                        hint_ind + 1,
+                        hints.len(),
+                    ));
+                }
+                text.push_str(hint);
+                text.push('\n');
+            }
+
+            pager(
+                stdout,
+                &text,
+                self.messages.hint,
+                false,
+                self.app_state.theme(),
+            )?;
+        }
+
+        self.render(stdout)?;
+        self.terminal_event_unpause_sender.send(())?;
+
+        Ok(())
+    }
+
+    /// Hiding the hints also hides the ones that are already shown.
+    pub fn toggle_hints(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        if self.hints_disabled {
+            return Ok(());
+        }
+
+        self.hints_hidden = !self.hints_hidden;
+        if self.hints_hidden {
+            self.n_shown_hints = 0;
+        }
+
+        self.rerender(stdout)?;
+
+        Ok(())
+    }
+
+    pub fn check_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<ExercisesProgress> {
+        // Ignore any input until checking all exercises is done.
+        let _input_pause_guard = InputPauseGuard::scoped_pause();
+
+        self.record_time_spent()?;
+
+        self.last_frame.clear();
+        self.failures = self.app_state.check_all_exercises_failures(stdout)?;
+        let first_pending_exercise_ind = self.app_state.first_pending_of_failures(&self.failures);
+        self.refresh_term_width();
+        if let Some(first_pending_exercise_ind) = first_pending_exercise_ind {
             // Only change exercise if the current one is done.
             if self.app_state.current_exercise().done {
+                self.history.push(self.app_state.current_exercise_ind());
                 self.app_state
                     .set_current_exercise_ind(first_pending_exercise_ind)?;
                 Ok(ExercisesProgress::NewPending)
@@ -287,6 +1895,21 @@ impl<'a> WatchState<'a> {
         }
     }
 
+    /// Mark all exercises as done and show the final message.
+    pub fn finish_all_exercises(&mut self, stdout: &mut StdoutLock) -> Result<()> {
+        self.record_time_spent()?;
+        self.app_state.mark_all_done()?;
+        self.last_frame.clear();
+        self.app_state.render_final_message(stdout)
+    }
+
+    // Resize events are only handled after long operations like running an exercise.
+    // Get the current width to render with it right away. The queued resize events are then
+    // ignored because the width didn't change.
+    fn refresh_term_width(&mut self) {
+        self.term_width = term::term_width();
+    }
+
     pub fn update_term_width(&mut self, width: u16, stdout: &mut StdoutLock) -> io::Result<()> {
         if self.term_width != width {
             self.term_width = width;
@@ -4,22 +4,58 @@ use std::sync::{
     mpsc::{Receiver, Sender},
 };
 
-use super::{WatchEvent, EXERCISE_RUNNING};
+use crate::keys::Keys;
+
+use super::{WatchEvent, AUTO_ADVANCE_ID, EXERCISE_RUNNING};
 
 pub enum InputEvent {
     Next,
+    Prev,
+    /// Return to the exercise before the last jump.
+    Back,
+    Skip,
     Run,
     Hint,
+    /// Hide or show the availability of hints for the rest of the session.
+    ToggleHints,
+    /// Show the hints in a scrollable view instead of below the output.
+    HintPager,
+    Bookmark,
+    CopySolution,
+    Peek,
+    Diff,
+    Note,
+    /// Report a problem with the current exercise (e.g. a wrong hint).
+    Report,
+    Groups,
+    Stats,
+    Wrap,
+    /// Stop or continue running the exercise when its file changes.
+    PauseWatcher,
+    /// Render again without rerunning the exercise.
+    Redraw,
+    View,
+    Edit,
     List,
+    Check,
     CheckAll,
+    /// Move on to the next exercise that failed when checking all exercises.
+    NextFailure,
     Reset,
+    Undo,
+    Reload,
     Quit,
+    FinishAll,
+    /// A key without a command. Reveals the prompt in the focus mode.
+    Other,
 }
 
 pub fn terminal_event_handler(
     sender: Sender<WatchEvent>,
     unpause_receiver: Receiver<()>,
-    manual_run: bool,
+    keys: Keys,
+    dev: bool,
+    reload: bool,
 ) {
     let last_watch_event = loop {
         match event::read() {
@@ -29,17 +65,117 @@ pub fn terminal_event_handler(
                     KeyEventKind::Press => (),
                 }
 
+                // Any key cancels a scheduled auto-advance.
+                AUTO_ADVANCE_ID.fetch_add(1, Relaxed);
+
                 if EXERCISE_RUNNING.load(Relaxed) {
                     continue;
                 }
 
-                let input_event = match key.code {
-                    KeyCode::Char('n') => InputEvent::Next,
-                    KeyCode::Char('r') if manual_run => InputEvent::Run,
-                    KeyCode::Char('h') => InputEvent::Hint,
-                    KeyCode::Char('l') => break WatchEvent::Input(InputEvent::List),
-                    KeyCode::Char('c') => InputEvent::CheckAll,
-                    KeyCode::Char('x') => {
+                let KeyCode::Char(c) = key.code else {
+                    if sender.send(WatchEvent::Input(InputEvent::Other)).is_err() {
+                        return;
+                    }
+
+                    continue;
+                };
+
+                let input_event = match c {
+                    c if c == keys.next => InputEvent::Next,
+                    c if c == keys.prev => InputEvent::Prev,
+                    c if c == keys.back => InputEvent::Back,
+                    c if c == keys.skip => InputEvent::Skip,
+                    c if c == keys.run => InputEvent::Run,
+                    c if c == keys.hint => InputEvent::Hint,
+                    c if c == keys.toggle_hints => InputEvent::ToggleHints,
+                    c if c == keys.hint_pager => {
+                        if sender
+                            .send(WatchEvent::Input(InputEvent::HintPager))
+                            .is_err()
+                        {
+                            return;
+                        }
+
+                        // The pager reads the input itself until returning.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    c if c == keys.bookmark => InputEvent::Bookmark,
+                    c if c == keys.copy_solution => InputEvent::CopySolution,
+                    c if c == keys.peek => InputEvent::Peek,
+                    c if c == keys.diff => InputEvent::Diff,
+                    c if c == keys.note => {
+                        if sender.send(WatchEvent::Input(InputEvent::Note)).is_err() {
+                            return;
+                        }
+
+                        // Stop reading the input while the note is typed.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    c if c == keys.report => {
+                        if sender.send(WatchEvent::Input(InputEvent::Report)).is_err() {
+                            return;
+                        }
+
+                        // Stop reading the input while the report is typed.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    c if c == keys.groups => InputEvent::Groups,
+                    c if c == keys.stats => {
+                        if sender.send(WatchEvent::Input(InputEvent::Stats)).is_err() {
+                            return;
+                        }
+
+                        // Pause input until returning from the stats screen.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    c if c == keys.wrap => InputEvent::Wrap,
+                    c if c == keys.pause_watcher => InputEvent::PauseWatcher,
+                    c if c == keys.redraw => InputEvent::Redraw,
+                    c if c == keys.view => {
+                        if sender.send(WatchEvent::Input(InputEvent::View)).is_err() {
+                            return;
+                        }
+
+                        // The view reads the input itself until returning.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    c if c == keys.edit => {
+                        if sender.send(WatchEvent::Input(InputEvent::Edit)).is_err() {
+                            return;
+                        }
+
+                        // Stop reading the input while the editor is open.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    c if c == keys.list => break WatchEvent::Input(InputEvent::List),
+                    c if c == keys.check => InputEvent::Check,
+                    c if c == keys.check_all => InputEvent::CheckAll,
+                    c if c == keys.next_failure => InputEvent::NextFailure,
+                    c if c == keys.reset => {
                         if sender.send(WatchEvent::Input(InputEvent::Reset)).is_err() {
                             return;
                         }
@@ -51,8 +187,24 @@ pub fn terminal_event_handler(
 
                         continue;
                     }
-                    KeyCode::Char('q') => break WatchEvent::Input(InputEvent::Quit),
-                    _ => continue,
+                    c if c == keys.undo => InputEvent::Undo,
+                    c if reload && c == keys.reload => break WatchEvent::Input(InputEvent::Reload),
+                    c if c == keys.quit => {
+                        if sender.send(WatchEvent::Input(InputEvent::Quit)).is_err() {
+                            return;
+                        }
+
+                        // Pause input until quitting the confirmation prompt.
+                        // The receiver fails if the watch mode was quit.
+                        if unpause_receiver.recv().is_err() {
+                            return;
+                        };
+
+                        continue;
+                    }
+                    // Hidden command for testing the final screen.
+                    'F' if dev => InputEvent::FinishAll,
+                    _ => InputEvent::Other,
                 };
 
                 if sender.send(WatchEvent::Input(input_event)).is_err() {
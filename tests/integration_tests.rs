@@ -130,6 +130,81 @@ fn run_test_failure() {
         .fail();
 }
 
+#[test]
+fn run_json() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["run", "--json", "compilation_success"])
+        .output(PartialStdout(
+            r#"{"name":"compilation_success","success":true,"#,
+        ))
+        .success();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["run", "--json", "compilation_failure"])
+        .output(PartialStdout(
+            r#"{"name":"compilation_failure","success":false,"#,
+        ))
+        .fail();
+
+    // The output of the exercise is on stdout and the compiler errors on stderr.
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["run", "--json", "test_success"])
+        .output(PartialStdout(
+            r#"Output from `main` function\n\n","stderr":"","#,
+        ))
+        .success();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["run", "--json", "compilation_failure"])
+        .output(PartialStdout(r#","stdout":"\n","stderr":"error: "#))
+        .fail();
+}
+
+#[test]
+fn check_all_json() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["check-all", "--json"])
+        .output(PartialStdout(
+            r#"[{"name":"compilation_success","success":true,"#,
+        ))
+        .fail();
+}
+
+#[test]
+fn check_all_dry_run() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["check-all", "--dry-run"])
+        .output(PartialStdout("passed, "))
+        .fail();
+}
+
+#[test]
+fn bench() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["bench"])
+        .output(PartialStdout("\nTotal "))
+        .success();
+}
+
+#[test]
+fn check_stdin_empty_source() {
+    // The input is always empty without a `main` function.
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["check-stdin", "compilation_success"])
+        .output(PartialStdout(
+            "The source code doesn't pass the checks of the exercise `compilation_success`",
+        ))
+        .fail();
+}
+
 #[test]
 fn run_exercise_not_in_info() {
     Cmd::default()
@@ -180,3 +255,76 @@ fn init() {
         .output(PartialStderr("already initialized"))
         .fail();
 }
+
+#[test]
+fn state_path() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["--state-path"])
+        .output(PartialStdout(".rustlings-state.txt\n"))
+        .success();
+}
+
+#[test]
+fn list() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["list", "--pending-only"])
+        .output(PartialStdout("2\tcompilation_failure\tpending\n"))
+        .success();
+}
+
+#[test]
+fn report() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["report", "-"])
+        .output(PartialStdout("| compilation_success |"))
+        .success();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["report", "--format", "json", "-"])
+        .output(PartialStdout(r#""name": "test_failure","#))
+        .success();
+}
+
+#[test]
+fn info() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["info"])
+        .output(PartialStdout(
+            r#"{"name":"test_failure","dir":null,"path":"exercises/test_failure.rs","solution_path":"solutions/test_failure.rs","test":true,"strict_clippy":false,"deny_warnings":false,"hints":["The answer to everything: 42"],"#,
+        ))
+        .success();
+}
+
+#[test]
+fn filter() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["--filter", "compilation_*", "check-all", "--json"])
+        .output(PartialStdout(
+            r#"[{"name":"compilation_success","success":true,"#,
+        ))
+        .fail();
+
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&["--filter", "not_matching", "run"])
+        .fail();
+}
+
+#[test]
+fn invalid_rustflags() {
+    Cmd::default()
+        .current_dir("tests/test_exercises")
+        .args(&[
+            "--rustflags",
+            "--invalid-flag",
+            "run",
+            "compilation_success",
+        ])
+        .fail();
+}